@@ -1,27 +1,65 @@
+use options::{DefaultWikiLinkResolver, Directive, DirectiveKind, WikiLinkResolver};
 use parser::Block;
 use parser::Block::{
-    Blockquote, CodeBlock, Header, Hr, LinkReference, OrderedList, Paragraph, Raw, UnorderedList,
+    AbbreviationDefinition, Admonition, Blockquote, CodeBlock, ContainerDirective, DefinitionList,
+    DisplayMath, FootnoteDefinition, FrontMatter, Header, Hr, LeafDirective, LinkReference,
+    OrderedList, Paragraph, Raw, Table, UnorderedList,
+};
+use parser::Span::{
+    AutoLink, Break, Code, Emphasis, FootnoteReference, Html, Image, InlineDirective,
+    InlineDisplayMath, InlineMath, Link, Literal, Mark, RefImage, RefLink, Reference, SoftBreak,
+    Strikethrough, Strong, Subscript, Superscript, Text, WikiLink,
+};
+use parser::{
+    AdmonitionKind, Alignment, Attributes, ListItem, OrderedListType, ReferenceKind, Span,
 };
-use parser::Span::{Break, Code, Emphasis, Image, Link, Literal, RefLink, Strong, Text};
-use parser::{ListItem, OrderedListType, Span};
 use regex::Regex;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use Options;
 
 type LinkReferenceMap<'a> = HashMap<&'a str, (&'a str, &'a Option<String>)>;
 
+// finds the definition of a reference link or image by its reference, or its
+// text if the reference is empty like in `[Example][]`
+fn find_link_reference<'a, 'b>(
+    content: &[Span],
+    reference: &str,
+    ctx: &'b Context<'a>,
+) -> Option<&'b (&'a str, &'a Option<String>)> {
+    ctx.link_references
+        .get::<str>(reference)
+        .or_else(|| ctx.link_references.get::<str>(&slugify(content, false)))
+}
+
 // takes a number of elements and returns their collective text as a slug
 fn slugify(elements: &[Span], no_spaces: bool) -> String {
     let mut ret = String::new();
 
     for el in elements {
         let next = match *el {
-            Break => "".to_owned(),
+            Break | SoftBreak => "".to_owned(),
             Literal(character) => character.to_string(),
-            Text(ref text) | Image(ref text, _, _) | Code(ref text) => text.trim().to_lowercase(),
+            Text(ref text)
+            | Image(ref text, _, _, _)
+            | RefImage(ref text, _, _)
+            | Code(ref text)
+            | InlineMath(ref text)
+            | InlineDisplayMath(ref text)
+            | AutoLink(ref text, _, _)
+            | Reference(_, ref text, _) => text.trim().to_lowercase(),
             RefLink(ref content, _, _)
-            | Link(ref content, _, _)
+            | Link(ref content, _, _, _)
             | Strong(ref content)
-            | Emphasis(ref content) => slugify(content, no_spaces),
+            | Emphasis(ref content)
+            | Strikethrough(ref content)
+            | Superscript(ref content)
+            | Subscript(ref content)
+            | Mark(ref content)
+            | InlineDirective(_, ref content, _) => slugify(content, no_spaces),
+            WikiLink(ref page, ref label) => label.as_ref().unwrap_or(page).trim().to_lowercase(),
+            FootnoteReference(_) | Html(_) => "".to_owned(),
         };
         if !ret.is_empty() {
             ret.push('_');
@@ -36,30 +74,212 @@ fn slugify(elements: &[Span], no_spaces: bool) -> String {
     ret
 }
 
-pub fn to_html(blocks: &[Block]) -> String {
-    let mut ret = String::new();
-    let mut link_references: LinkReferenceMap = HashMap::new();
-    for block in blocks.iter() {
-        match block {
-            LinkReference(ref id, ref text, ref title) => {
-                link_references.insert(id, (text, title));
-            }
-            _ => {}
+// Document-wide information that is needed to render individual blocks and spans.
+struct Context<'a> {
+    link_references: LinkReferenceMap<'a>,
+    // abbreviations mapped to their title, the longest abbreviation first
+    abbreviations: Vec<(&'a str, &'a str)>,
+    footnote_definitions: HashMap<String, &'a [Block]>,
+    // the content, level and attributes of every header, for the table of contents
+    headers: Vec<(&'a [Span], usize, &'a Option<Attributes>)>,
+    // footnote ids mapped to their number, in the order of their first reference
+    footnote_numbers: HashMap<String, usize>,
+    // numbers of the footnotes whose first reference has already been rendered
+    rendered_footnote_references: RefCell<HashSet<usize>>,
+    options: &'a Options,
+    // state of the pseudo-random number generator used for obfuscating emails
+    random_state: Cell<u64>,
+}
+
+impl<'a> Context<'a> {
+    fn new(blocks: &'a [Block], options: &'a Options) -> Context<'a> {
+        let mut ctx = Context {
+            link_references: HashMap::new(),
+            abbreviations: vec![],
+            footnote_definitions: HashMap::new(),
+            headers: vec![],
+            footnote_numbers: HashMap::new(),
+            rendered_footnote_references: RefCell::new(HashSet::new()),
+            options,
+            random_state: Cell::new(options.email_obfuscation_seed),
         };
+        let mut footnote_references = vec![];
+        ctx.collect_blocks(blocks, &mut footnote_references);
+        ctx.abbreviations
+            .sort_by_key(|&(abbr, _)| Reverse(abbr.len()));
+
+        for id in footnote_references {
+            if ctx.footnote_definitions.contains_key(&id) && !ctx.footnote_numbers.contains_key(&id)
+            {
+                let number = ctx.footnote_numbers.len() + 1;
+                ctx.footnote_numbers.insert(id, number);
+            }
+        }
+        ctx
+    }
+
+    fn collect_blocks(&mut self, blocks: &'a [Block], footnote_references: &mut Vec<String>) {
+        for block in blocks {
+            match *block {
+                LinkReference(ref id, ref text, ref title) => {
+                    self.link_references.insert(id, (text, title));
+                }
+                AbbreviationDefinition(ref abbr, ref title) => {
+                    // a later definition of the same abbreviation replaces the earlier one
+                    self.abbreviations.retain(|&(a, _)| a != abbr);
+                    self.abbreviations.push((abbr, title));
+                }
+                FootnoteDefinition(ref id, ref content) => {
+                    self.footnote_definitions.insert(id.to_lowercase(), content);
+                    self.collect_blocks(content, footnote_references);
+                }
+                Header(ref elements, level, ref attributes) => {
+                    self.headers.push((elements, level, attributes));
+                    collect_footnote_references(elements, footnote_references)
+                }
+                Paragraph(ref elements) => {
+                    collect_footnote_references(elements, footnote_references)
+                }
+                Blockquote(ref elements) | Admonition(_, ref elements) => {
+                    self.collect_blocks(elements, footnote_references)
+                }
+                LeafDirective(_, ref label, _) => {
+                    collect_footnote_references(label, footnote_references)
+                }
+                ContainerDirective(_, ref label, _, ref content) => {
+                    collect_footnote_references(label, footnote_references);
+                    self.collect_blocks(content, footnote_references);
+                }
+                UnorderedList(ref elements) | OrderedList(ref elements, _, _, _) => {
+                    for list_item in elements {
+                        match *list_item {
+                            ListItem::Simple(ref els, _) => {
+                                collect_footnote_references(els, footnote_references)
+                            }
+                            ListItem::Paragraph(ref paragraphs, _) => {
+                                self.collect_blocks(paragraphs, footnote_references)
+                            }
+                        }
+                    }
+                }
+                DefinitionList(ref entries) => {
+                    for (term, definitions) in entries {
+                        collect_footnote_references(term, footnote_references);
+                        for definition in definitions {
+                            self.collect_blocks(definition, footnote_references);
+                        }
+                    }
+                }
+                Table(ref header, _, ref rows) => {
+                    for cell in header.iter().chain(rows.iter().flat_map(|row| row.iter())) {
+                        collect_footnote_references(cell, footnote_references);
+                    }
+                }
+                FrontMatter(_, _) | CodeBlock(_, _, _) | DisplayMath(_) | Raw(_) | Hr => {}
+            }
+        }
     }
+
+    // returns the next number of a SplitMix64 sequence, which is good enough
+    // for obfuscation and keeps the output stable for a given seed
+    fn next_random(&self) -> u64 {
+        let state = self.random_state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+        self.random_state.set(state);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+fn collect_footnote_references(elements: &[Span], footnote_references: &mut Vec<String>) {
+    for element in elements {
+        match *element {
+            FootnoteReference(ref id) => footnote_references.push(id.to_lowercase()),
+            RefLink(ref content, _, _)
+            | Link(ref content, _, _, _)
+            | Strong(ref content)
+            | Emphasis(ref content)
+            | Strikethrough(ref content)
+            | Superscript(ref content)
+            | Subscript(ref content)
+            | Mark(ref content)
+            | InlineDirective(_, ref content, _) => {
+                collect_footnote_references(content, footnote_references)
+            }
+            Break
+            | SoftBreak
+            | Literal(_)
+            | Text(_)
+            | Code(_)
+            | InlineMath(_)
+            | InlineDisplayMath(_)
+            | WikiLink(_, _)
+            | Image(_, _, _, _)
+            | RefImage(_, _, _)
+            | AutoLink(_, _, _)
+            | Reference(_, _, _)
+            | Html(_) => {}
+        }
+    }
+}
+
+pub fn to_html(blocks: &[Block], options: &Options) -> String {
+    let ctx = Context::new(blocks, options);
+    let mut ret = format_blocks(blocks, &ctx);
+    ret.push_str(&format_footnotes(&ctx));
+    ret
+}
+
+fn format_blocks(blocks: &[Block], ctx: &Context) -> String {
+    let mut ret = String::new();
     for block in blocks.iter() {
         let next = match block {
-            Header(ref elements, level) => format_header(elements, *level, &link_references),
-            Paragraph(ref elements) => format_paragraph(elements, &link_references),
-            Blockquote(ref elements) => format_blockquote(elements),
-            CodeBlock(ref lang, ref elements) => format_codeblock(lang, elements),
-            UnorderedList(ref elements) => format_unordered_list(elements, &link_references),
-            OrderedList(ref elements, ref num_type) => {
-                format_ordered_list(elements, num_type, &link_references)
-            }
-            LinkReference(_, _, _) => "".to_owned(),
-            Raw(ref elements) => elements.to_owned(),
-            Hr => format!("<hr />\n\n"),
+            Header(ref elements, level, ref attributes) => {
+                format_header(elements, *level, attributes, ctx)
+            }
+            Paragraph(ref elements) if is_toc_marker(elements, ctx) => {
+                format_table_of_contents(ctx)
+            }
+            Paragraph(ref elements) => format_paragraph(elements, ctx),
+            Blockquote(ref elements) => format_blockquote(elements, ctx),
+            Admonition(kind, ref elements) => format_admonition(*kind, elements, ctx),
+            LeafDirective(ref name, ref label, ref attributes) => format!(
+                "{}\n\n",
+                format_directive(DirectiveKind::Leaf, name, label, attributes, "", ctx)
+            ),
+            ContainerDirective(ref name, ref label, ref attributes, ref content) => format!(
+                "{}\n\n",
+                format_directive(
+                    DirectiveKind::Container,
+                    name,
+                    label,
+                    attributes,
+                    &format_blocks(content, ctx),
+                    ctx
+                )
+            ),
+            CodeBlock(ref lang, ref elements, ref attributes) => {
+                format_codeblock(lang, elements, attributes)
+            }
+            DisplayMath(ref tex) => format!(
+                "<div class=\"math display\">\\[{}\\]</div>\n\n",
+                escape_math(tex)
+            ),
+            UnorderedList(ref elements) => format_unordered_list(elements, ctx),
+            OrderedList(ref elements, ref num_type, ref start, _) => {
+                format_ordered_list(elements, num_type, *start, ctx)
+            }
+            DefinitionList(ref entries) => format_definition_list(entries, ctx),
+            Table(ref header, ref alignments, ref rows) => {
+                format_table(header, alignments, rows, ctx)
+            }
+            FrontMatter(_, _) | LinkReference(_, _, _) | AbbreviationDefinition(_, _) => {
+                "".to_owned()
+            }
+            FootnoteDefinition(ref id, ref content) => format_footnote_definition(id, content, ctx),
+            Raw(ref elements) => format!("{}\n\n", elements),
+            Hr => "<hr />\n\n".to_owned(),
         };
         ret.push_str(&next)
     }
@@ -68,84 +288,238 @@ pub fn to_html(blocks: &[Block]) -> String {
     ret
 }
 
-fn format_spans(elements: &[Span], link_references: &LinkReferenceMap) -> String {
+fn format_spans(elements: &[Span], ctx: &Context) -> String {
     let mut ret = String::new();
     for element in elements.iter() {
         let next = match *element {
-            Break => format!("<br />"),
+            Break => "<br />".to_owned(),
+            SoftBreak => "\n".to_owned(),
             Literal(character) => character.to_string(),
-            Text(ref text) => format!("{}", &escape(text, true)),
+            Text(ref text) => format_text(text, ctx),
             Code(ref text) => format!("<code>{}</code>", &escape(text, false)),
-            Link(ref content, ref url, None) => format!(
-                "<a href=\"{}\">{}</a>",
-                &escape(url, false),
-                format_spans(content, link_references)
+            InlineMath(ref tex) => format!(
+                "<span class=\"math inline\">\\({}\\)</span>",
+                escape_math(tex)
             ),
-            Link(ref content, ref url, Some(ref title)) => format!(
-                "<a href=\"{}\" title=\"{}\">{}</a>",
-                &escape(url, false),
-                &escape(title, true),
-                format_spans(content, link_references)
+            InlineDisplayMath(ref tex) => format!(
+                "<span class=\"math display\">\\[{}\\]</span>",
+                escape_math(tex)
             ),
+            Link(ref content, ref url, ref title, ref attributes) => {
+                format_link(content, url, title, attributes, ctx)
+            }
+            AutoLink(ref text, ref url, _) => {
+                format_link(&[Text(text.to_owned())], url, &None, &None, ctx)
+            }
+            Reference(kind, ref text, ref url) => format_reference(kind, text, url, ctx),
             RefLink(ref content, ref reference, ref raw) => {
-                if let Some((ref url, None)) = link_references.get::<str>(reference) {
-                    format!(
-                        "<a href=\"{}\">{}</a>",
-                        &escape(url, false),
-                        format_spans(content, link_references)
-                    )
-                } else if let Some((ref url, Some(ref title))) =
-                    link_references.get::<str>(reference)
-                {
-                    format!(
-                        "<a href=\"{}\" title=\"{}\">{}</a>",
-                        &escape(url, false),
-                        &escape(title, true),
-                        format_spans(content, link_references)
-                    )
-                } else if let Some((ref url, None)) =
-                    link_references.get::<str>(&slugify(content, false))
-                {
-                    format!(
-                        "<a href=\"{}\">{}</a>",
-                        &escape(url, false),
-                        format_spans(content, link_references)
-                    )
-                } else if let Some((ref url, Some(ref title))) =
-                    link_references.get::<str>(&slugify(content, false))
-                {
-                    format!(
-                        "<a href=\"{}\" title=\"{}\">{}</a>",
-                        &escape(url, false),
-                        &escape(title, true),
-                        format_spans(content, link_references)
-                    )
-                } else {
-                    raw.to_owned()
+                match find_link_reference(content, reference, ctx) {
+                    Some(&(url, title)) => format_link(content, url, title, &None, ctx),
+                    None => escape(raw, true),
                 }
             }
-            Image(ref text, ref url, None) => format!(
-                "<img src=\"{}\" alt=\"{}\" />",
-                &escape(url, false),
-                &escape(text, true)
-            ),
-            Image(ref text, ref url, Some(ref title)) => format!(
-                "<img src=\"{}\" title=\"{}\" alt=\"{}\" />",
-                &escape(url, false),
-                &escape(title, true),
-                &escape(text, true)
-            ),
-            Emphasis(ref content) => format!("<em>{}</em>", format_spans(content, link_references)),
-            Strong(ref content) => format!(
-                "<strong>{}</strong>",
-                format_spans(content, link_references)
-            ),
+            Image(ref text, ref url, ref title, ref attributes) => {
+                format_image(text, url, title, attributes)
+            }
+            RefImage(ref text, ref reference, ref raw) => {
+                match find_link_reference(&[Text(text.to_owned())], reference, ctx) {
+                    Some(&(url, title)) => format_image(text, url, title, &None),
+                    None => escape(raw, true),
+                }
+            }
+            Emphasis(ref content) => format!("<em>{}</em>", format_spans(content, ctx)),
+            Strong(ref content) => format!("<strong>{}</strong>", format_spans(content, ctx)),
+            Strikethrough(ref content) => {
+                format!("<del>{}</del>", format_spans(content, ctx))
+            }
+            Superscript(ref content) => format!("<sup>{}</sup>", format_spans(content, ctx)),
+            Subscript(ref content) => format!("<sub>{}</sub>", format_spans(content, ctx)),
+            Mark(ref content) => format!("<mark>{}</mark>", format_spans(content, ctx)),
+            InlineDirective(ref name, ref label, ref attributes) => {
+                format_directive(DirectiveKind::Inline, name, label, attributes, "", ctx)
+            }
+            WikiLink(ref page, ref label) => format_wiki_link(page, label, ctx),
+            FootnoteReference(ref id) => format_footnote_reference(id, ctx),
+            Html(ref html) => html.to_owned(),
         };
         ret.push_str(&next)
     }
     ret
 }
 
+// escapes the text and wraps whole-word occurrences of the defined abbreviations
+fn format_text(text: &str, ctx: &Context) -> String {
+    if ctx.abbreviations.is_empty() {
+        return escape(text, true);
+    }
+
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut ret = String::new();
+    // the start of the text that hasn't been added to ret yet
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        if i < start {
+            prev = Some(c);
+            continue;
+        }
+        if !prev.is_some_and(is_word_char) {
+            let abbreviation = ctx.abbreviations.iter().find(|&&(abbr, _)| {
+                text[i..].starts_with(abbr) && !text[i + abbr.len()..].starts_with(is_word_char)
+            });
+            if let Some(&(abbr, title)) = abbreviation {
+                ret.push_str(&escape(&text[start..i], true));
+                if title.is_empty() {
+                    ret.push_str(&format!("<abbr>{}</abbr>", escape(abbr, true)));
+                } else {
+                    ret.push_str(&format!(
+                        "<abbr title=\"{}\">{}</abbr>",
+                        escape(title, true),
+                        escape(abbr, true)
+                    ));
+                }
+                start = i + abbr.len();
+            }
+        }
+        prev = Some(c);
+    }
+    ret.push_str(&escape(&text[start..], true));
+    ret
+}
+
+fn format_link(
+    content: &[Span],
+    url: &str,
+    title: &Option<String>,
+    attributes: &Option<Attributes>,
+    ctx: &Context,
+) -> String {
+    let obfuscate = ctx.options.obfuscate_emails && url.starts_with("mailto:");
+    let href = if obfuscate {
+        obfuscate_email(url, ctx)
+    } else {
+        escape(url, false)
+    };
+    let content = match *content {
+        [Text(ref text)] if obfuscate => obfuscate_email(text, ctx),
+        _ => format_spans(content, ctx),
+    };
+    match *title {
+        Some(ref title) => format!(
+            "<a href=\"{}\" title=\"{}\"{}>{}</a>",
+            href,
+            &escape(title, true),
+            format_attributes(attributes, true),
+            content
+        ),
+        None => format!(
+            "<a href=\"{}\"{}>{}</a>",
+            href,
+            format_attributes(attributes, true),
+            content
+        ),
+    }
+}
+
+// formats an attribute list as HTML attributes, each preceded by a space.
+// The id is left out if `with_id` is false, e.g. because it is added separately.
+fn format_attributes(attributes: &Option<Attributes>, with_id: bool) -> String {
+    let mut ret = String::new();
+    if let Some(ref attributes) = *attributes {
+        if let (true, Some(ref id)) = (with_id, &attributes.id) {
+            ret.push_str(&format!(" id=\"{}\"", escape(id, false)));
+        }
+        if !attributes.classes.is_empty() {
+            ret.push_str(&format!(
+                " class=\"{}\"",
+                escape(&attributes.classes.join(" "), false)
+            ));
+        }
+        for (key, value) in &attributes.pairs {
+            ret.push_str(&format!(" {}=\"{}\"", key, escape(value, false)));
+        }
+    }
+    ret
+}
+
+fn format_image(
+    text: &str,
+    url: &str,
+    title: &Option<String>,
+    attributes: &Option<Attributes>,
+) -> String {
+    match *title {
+        None => format!(
+            "<img src=\"{}\" alt=\"{}\"{} />",
+            &escape(url, false),
+            &escape(text, true),
+            format_attributes(attributes, true)
+        ),
+        Some(ref title) => format!(
+            "<img src=\"{}\" title=\"{}\" alt=\"{}\"{} />",
+            &escape(url, false),
+            &escape(title, true),
+            &escape(text, true),
+            format_attributes(attributes, true)
+        ),
+    }
+}
+
+// links a reference with the class GitHub uses for its kind
+fn format_reference(kind: ReferenceKind, text: &str, url: &str, ctx: &Context) -> String {
+    let class = match kind {
+        ReferenceKind::Issue => "issue-link",
+        ReferenceKind::Commit => "commit-link",
+        ReferenceKind::Mention => "user-mention",
+    };
+    let attributes = Some(Attributes {
+        classes: vec![class.to_owned()],
+        ..Default::default()
+    });
+    format_link(&[Text(text.to_owned())], url, &None, &attributes, ctx)
+}
+
+fn format_wiki_link(page: &str, label: &Option<String>, ctx: &Context) -> String {
+    let resolver: &dyn WikiLinkResolver = match ctx.options.wiki_link_resolver {
+        Some(ref resolver) => &**resolver,
+        None => &DefaultWikiLinkResolver,
+    };
+    format!(
+        "<a href=\"{}\"{}>{}</a>",
+        escape(&resolver.url(page), false),
+        if resolver.exists(page) {
+            ""
+        } else {
+            " class=\"new\""
+        },
+        escape(label.as_ref().map_or(page, |label| label.as_str()), true)
+    )
+}
+
+// Encodes an email address as a random mix of decimal and hexadecimal
+// character entities, like Markdown.pl does, to make it harder to harvest.
+fn obfuscate_email(text: &str, ctx: &Context) -> String {
+    let mut ret = String::new();
+    for c in text.chars() {
+        // the @ is always encoded, the colon of mailto: is always left alone
+        let r = if c == '@' {
+            ctx.next_random() % 90
+        } else if c == ':' {
+            90
+        } else {
+            ctx.next_random() % 100
+        };
+        if r >= 90 {
+            ret.push_str(&escape(&c.to_string(), false));
+        } else if r < 45 {
+            ret.push_str(&format!("&#x{:x};", c as u32));
+        } else {
+            ret.push_str(&format!("&#{};", c as u32));
+        }
+    }
+    ret
+}
+
 fn escape(text: &str, replace_entities: bool) -> String {
     lazy_static! {
         static ref AMPERSAND: Regex = Regex::new(r"&amp;(?P<x>\S+;)").unwrap();
@@ -155,7 +529,6 @@ fn escape(text: &str, replace_entities: bool) -> String {
         .replace("&", "&amp;")
         .replace("<", "&lt;")
         .replace("\"", "&quot;")
-        .replace("'", "&#8217;")
         .replace(">", "&gt;");
 
     // We can't do lookarounds in the regex crate to match only ampersands with
@@ -165,77 +538,480 @@ fn escape(text: &str, replace_entities: bool) -> String {
         return AMPERSAND.replace_all(&replaced, "&$x").into_owned();
     }
 
-    return replaced;
+    replaced
 }
 
-fn format_list(
-    elements: &[ListItem],
-    start_tag: &str,
-    end_tag: &str,
-    link_references: &LinkReferenceMap,
-) -> String {
+// escapes TeX for use in HTML while keeping it intact for client-side
+// renderers, which read it from the text content of the element
+fn escape_math(text: &str) -> String {
+    text.replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;")
+}
+
+fn format_list(elements: &[ListItem], start_tag: &str, end_tag: &str, ctx: &Context) -> String {
     let mut ret = String::new();
     for list_item in elements {
-        let mut content = String::new();
-        match *list_item {
-            ListItem::Simple(ref els) => content.push_str(&format_spans(els, link_references)),
-            ListItem::Paragraph(ref paragraphs) => {
-                content.push_str(&format!("\n{}", to_html(paragraphs)))
+        let (content, checked) = match *list_item {
+            ListItem::Simple(ref els, checked) => (format_spans(els, ctx), checked),
+            ListItem::Paragraph(ref paragraphs, checked) => {
+                (format!("\n{}", format_blocks(paragraphs, ctx)), checked)
             }
-        }
+        };
 
-        ret.push_str(&format!("\n<li>{}</li>\n", content))
+        let checkbox = match checked {
+            Some(true) => "<input type=\"checkbox\" disabled=\"\" checked=\"\" />",
+            Some(false) => "<input type=\"checkbox\" disabled=\"\" />",
+            None => {
+                ret.push_str(&format!("\n<li>{}</li>\n", content));
+                continue;
+            }
+        };
+        let separator = if content.starts_with('\n') { "" } else { " " };
+        ret.push_str(&format!(
+            "\n<li class=\"task-list-item\">{}{}{}</li>\n",
+            checkbox, separator, content
+        ))
     }
     format!("<{}>{}</{}>\n\n", start_tag, ret, end_tag)
 }
 
-fn format_unordered_list(elements: &[ListItem], link_references: &LinkReferenceMap) -> String {
-    format_list(elements, "ul", "ul", link_references)
+fn format_unordered_list(elements: &[ListItem], ctx: &Context) -> String {
+    format_list(elements, "ul", "ul", ctx)
 }
 
 fn format_ordered_list(
     elements: &[ListItem],
     num_type: &OrderedListType,
-    link_references: &LinkReferenceMap,
+    start: usize,
+    ctx: &Context,
 ) -> String {
+    let mut start_tag = "ol".to_owned();
     if num_type != &OrderedListType::Numeric {
-        format_list(
-            elements,
-            &format!("ol type=\"{}\"", num_type.to_str()),
-            "ol",
-            link_references,
-        )
-    } else {
-        format_list(elements, "ol", "ol", link_references)
+        start_tag.push_str(&format!(" type=\"{}\"", num_type.to_str()));
     }
+    if start != 1 {
+        start_tag.push_str(&format!(" start=\"{}\"", start));
+    }
+    format_list(elements, &start_tag, "ol", ctx)
 }
 
-fn format_codeblock(lang: &Option<String>, elements: &str) -> String {
+// The keys of a code block's attributes that configure the rendering
+// instead of being passed on to the HTML
+const CODE_BLOCK_OPTIONS: &[&str] = &["title", "linenos", "linenostart", "hl_lines"];
+
+fn format_codeblock(
+    lang: &Option<String>,
+    elements: &str,
+    attributes: &Option<Attributes>,
+) -> String {
+    let (title, code, html_attributes) = match *attributes {
+        Some(ref attributes) => {
+            let mut html_attributes = attributes.clone();
+            html_attributes
+                .pairs
+                .retain(|(key, _)| !CODE_BLOCK_OPTIONS.contains(&key.as_str()));
+            (
+                attributes.get("title"),
+                format_code_lines(elements, attributes),
+                Some(html_attributes),
+            )
+        }
+        None => (None, escape(elements, false), None),
+    };
+
+    let title = match title {
+        Some(title) => format!("<div class=\"code-title\">{}</div>\n", escape(title, true)),
+        None => String::new(),
+    };
+
     if lang.is_none() || (lang.is_some() && lang.as_ref().unwrap().is_empty()) {
-        format!("<pre><code>{}</code></pre>\n\n", &escape(elements, false))
+        format!(
+            "{}<pre{}><code>{}</code></pre>\n\n",
+            title,
+            format_attributes(&html_attributes, true),
+            code
+        )
     } else {
         format!(
-            "<pre><code class=\"language-{}\">{}</code></pre>\n\n",
+            "{}<pre{}><code class=\"language-{}\">{}</code></pre>\n\n",
+            title,
+            format_attributes(&html_attributes, true),
             &escape(lang.as_ref().unwrap(), false),
-            &escape(elements, false)
+            code
         )
     }
 }
 
-fn format_blockquote(elements: &[Block]) -> String {
-    format!("<blockquote>\n{}</blockquote>\n\n", to_html(elements))
+// wraps every line of the code in a span if line numbers are shown or lines
+// are highlighted, with `linenos=true` and e.g. `hl_lines="1 3-5"`
+fn format_code_lines(code: &str, attributes: &Attributes) -> String {
+    let line_numbers = attributes.get("linenos").is_some_and(|v| v != "false");
+    let highlighted = attributes.get("hl_lines").map(parse_line_ranges);
+    if !line_numbers && highlighted.is_none() {
+        return escape(code, false);
+    }
+
+    let start = attributes
+        .get("linenostart")
+        .and_then(|start| start.parse().ok())
+        .unwrap_or(1);
+    let highlighted = highlighted.unwrap_or_default();
+    code.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let class = if highlighted
+                .iter()
+                .any(|&(from, to)| from <= i + 1 && i < to)
+            {
+                "line highlighted"
+            } else {
+                "line"
+            };
+            let number = if line_numbers {
+                format!("<span class=\"line-number\">{}</span>", start + i)
+            } else {
+                String::new()
+            };
+            format!(
+                "<span class=\"{}\">{}{}</span>",
+                class,
+                number,
+                escape(line, false)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// parses line ranges like `1 3-5` or `1,3-5` into inclusive (from, to) pairs
+fn parse_line_ranges(ranges: &str) -> Vec<(usize, usize)> {
+    ranges
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|range| {
+            let mut bounds = range.splitn(2, '-').map(|n| n.trim().parse().ok());
+            let from = bounds.next()??;
+            match bounds.next() {
+                Some(to) => Some((from, to?)),
+                None => Some((from, from)),
+            }
+        })
+        .collect()
+}
+
+fn format_table_row(
+    cells: &[Vec<Span>],
+    alignments: &[Alignment],
+    tag: &str,
+    ctx: &Context,
+) -> String {
+    let mut ret = String::new();
+    for (cell, alignment) in cells.iter().zip(alignments) {
+        let style = match *alignment {
+            Alignment::Left => " style=\"text-align: left\"",
+            Alignment::Center => " style=\"text-align: center\"",
+            Alignment::Right => " style=\"text-align: right\"",
+            Alignment::None => "",
+        };
+        ret.push_str(&format!(
+            "<{}{}>{}</{}>\n",
+            tag,
+            style,
+            format_spans(cell, ctx),
+            tag
+        ));
+    }
+    format!("<tr>\n{}</tr>\n", ret)
+}
+
+fn format_table(
+    header: &[Vec<Span>],
+    alignments: &[Alignment],
+    rows: &[Vec<Vec<Span>>],
+    ctx: &Context,
+) -> String {
+    let mut ret = format!(
+        "<table>\n<thead>\n{}</thead>\n",
+        format_table_row(header, alignments, "th", ctx)
+    );
+    if !rows.is_empty() {
+        ret.push_str("<tbody>\n");
+        for row in rows {
+            ret.push_str(&format_table_row(row, alignments, "td", ctx));
+        }
+        ret.push_str("</tbody>\n");
+    }
+    ret.push_str("</table>\n\n");
+    ret
+}
+
+fn format_definition_list(entries: &[(Vec<Span>, Vec<Vec<Block>>)], ctx: &Context) -> String {
+    let mut ret = String::from("<dl>\n");
+    for (term, definitions) in entries {
+        ret.push_str(&format!("<dt>{}</dt>\n", format_spans(term, ctx)));
+        for definition in definitions {
+            match definition.as_slice() {
+                // a definition consisting of a single paragraph is rendered tight
+                [Paragraph(elements)] => {
+                    ret.push_str(&format!("<dd>{}</dd>\n", format_spans(elements, ctx)))
+                }
+                _ => ret.push_str(&format!("<dd>\n{}</dd>\n", format_blocks(definition, ctx))),
+            }
+        }
+    }
+    ret.push_str("</dl>\n\n");
+    ret
+}
+
+// renders a directive with the handler registered for its name, or as a
+// `<div>` or `<span>` with a `data-directive` attribute if there is none
+fn format_directive(
+    kind: DirectiveKind,
+    name: &str,
+    label: &[Span],
+    attributes: &Option<Attributes>,
+    content: &str,
+    ctx: &Context,
+) -> String {
+    let label = format_spans(label, ctx);
+    if let Some(handler) = ctx.options.directives.get(name) {
+        let no_attributes = Attributes::default();
+        return handler.render(&Directive {
+            kind,
+            name,
+            label: &label,
+            attributes: attributes.as_ref().unwrap_or(&no_attributes),
+            content,
+        });
+    }
+
+    let html_attributes = format!(
+        " data-directive=\"{}\"{}",
+        escape(name, false),
+        format_attributes(attributes, true)
+    );
+    match kind {
+        DirectiveKind::Inline => format!("<span{}>{}</span>", html_attributes, label),
+        DirectiveKind::Leaf => format!("<div{}>{}</div>", html_attributes, label),
+        DirectiveKind::Container if label.is_empty() => {
+            format!("<div{}>\n{}</div>", html_attributes, content)
+        }
+        DirectiveKind::Container => format!(
+            "<div{}>\n<p class=\"directive-label\">{}</p>\n{}</div>",
+            html_attributes, label, content
+        ),
+    }
+}
+
+fn format_blockquote(elements: &[Block], ctx: &Context) -> String {
+    format!(
+        "<blockquote>\n{}</blockquote>\n\n",
+        format_blocks(elements, ctx)
+    )
+}
+
+fn format_admonition(kind: AdmonitionKind, elements: &[Block], ctx: &Context) -> String {
+    let title = match kind {
+        AdmonitionKind::Note => "Note",
+        AdmonitionKind::Tip => "Tip",
+        AdmonitionKind::Important => "Important",
+        AdmonitionKind::Warning => "Warning",
+        AdmonitionKind::Caution => "Caution",
+    };
+    format!(
+        "<div class=\"markdown-alert markdown-alert-{}\">\n<p class=\"markdown-alert-title\">{}</p>\n{}</div>\n\n",
+        title.to_lowercase(),
+        title,
+        if elements.is_empty() {
+            String::new()
+        } else {
+            format_blocks(elements, ctx)
+        }
+    )
 }
 
-fn format_paragraph(elements: &[Span], link_references: &LinkReferenceMap) -> String {
-    format!("<p>{}</p>\n\n", format_spans(elements, link_references))
+fn format_paragraph(elements: &[Span], ctx: &Context) -> String {
+    format!("<p>{}</p>\n\n", format_spans(elements, ctx))
 }
 
-fn format_header(elements: &[Span], level: usize, link_references: &LinkReferenceMap) -> String {
+pub fn header_id(elements: &[Span], attributes: &Option<Attributes>) -> String {
+    // an explicit id overrides the generated one
+    let id = match *attributes {
+        Some(Attributes {
+            id: Some(ref id), ..
+        }) => id.to_owned(),
+        _ => slugify(elements, true),
+    };
+    // the id is used in both single and double quoted attributes
+    escape(&id, false).replace('\'', "&#39;")
+}
+
+fn format_header(
+    elements: &[Span],
+    level: usize,
+    attributes: &Option<Attributes>,
+    ctx: &Context,
+) -> String {
     format!(
-        "<h{} id='{}'>{}</h{}>\n\n",
+        "<h{} id='{}'{}>{}</h{}>\n\n",
         level,
-        slugify(elements, true),
-        format_spans(elements, link_references),
+        header_id(elements, attributes),
+        format_attributes(attributes, false),
+        format_spans(elements, ctx),
         level
     )
 }
+
+// whether the paragraph consists of only the marker of the table of contents.
+// A marker like `[TOC]` is a link if there is a link reference for it.
+fn is_toc_marker(elements: &[Span], ctx: &Context) -> bool {
+    let marker = ctx.options.toc_marker.as_deref().unwrap_or("[TOC]");
+    match *elements {
+        [Text(ref text)] => text == marker,
+        [RefLink(ref content, ref reference, ref raw)] => {
+            raw == marker && find_link_reference(content, reference, ctx).is_none()
+        }
+        _ => false,
+    }
+}
+
+// lists the headers as nested lists, where each list is inside of the item of
+// the previous header with a lower level
+fn format_table_of_contents(ctx: &Context) -> String {
+    let levels = ctx.options.toc_levels.clone().unwrap_or(1..=6);
+    let mut ret = String::from("<nav class=\"table-of-contents\">\n");
+    // the levels of the lists that are open
+    let mut open: Vec<usize> = vec![];
+    for &(elements, level, attributes) in &ctx.headers {
+        if !levels.contains(&level) {
+            continue;
+        }
+        // close the lists that are nested deeper than the header
+        while open.len() > 1 && open[open.len() - 2] >= level {
+            ret.push_str("</li>\n</ul>\n");
+            open.pop();
+        }
+        match open.last_mut() {
+            // skipped levels, like a `####` followed by a `##`, share a list
+            Some(l) if *l >= level => {
+                ret.push_str("</li>\n");
+                *l = level;
+            }
+            Some(_) => {
+                ret.push_str("\n<ul>\n");
+                open.push(level);
+            }
+            None => {
+                ret.push_str("<ul>\n");
+                open.push(level);
+            }
+        }
+        ret.push_str(&format!(
+            "<li><a href=\"#{}\">{}</a>",
+            header_id(elements, attributes),
+            escape(&plain_text(elements), true)
+        ));
+    }
+    for _ in open {
+        ret.push_str("</li>\n</ul>\n");
+    }
+    ret.push_str("</nav>\n\n");
+    ret
+}
+
+// the text of the spans without any markup, e.g. for the table of contents
+fn plain_text(elements: &[Span]) -> String {
+    let mut ret = String::new();
+    for element in elements {
+        match *element {
+            Text(ref text)
+            | Code(ref text)
+            | InlineMath(ref text)
+            | InlineDisplayMath(ref text)
+            | AutoLink(ref text, _, _)
+            | Reference(_, ref text, _) => ret.push_str(text),
+            Literal(character) => ret.push(character),
+            Break | SoftBreak => ret.push(' '),
+            RefLink(ref content, _, _)
+            | Link(ref content, _, _, _)
+            | Strong(ref content)
+            | Emphasis(ref content)
+            | Strikethrough(ref content)
+            | Superscript(ref content)
+            | Subscript(ref content)
+            | Mark(ref content)
+            | InlineDirective(_, ref content, _) => ret.push_str(&plain_text(content)),
+            Image(ref alt, _, _, _) | RefImage(ref alt, _, _) => ret.push_str(alt),
+            WikiLink(ref page, ref label) => ret.push_str(label.as_ref().unwrap_or(page)),
+            FootnoteReference(_) | Html(_) => {}
+        }
+    }
+    ret
+}
+
+fn format_footnote_reference(id: &str, ctx: &Context) -> String {
+    match ctx.footnote_numbers.get(&id.to_lowercase()) {
+        // only the first reference gets an id, which the footnote links back to
+        Some(&number) if ctx.rendered_footnote_references.borrow_mut().insert(number) => format!(
+            "<sup id=\"fnref:{}\"><a href=\"#fn:{}\" class=\"footnote-ref\">{}</a></sup>",
+            number, number, number
+        ),
+        Some(&number) => format!(
+            "<sup><a href=\"#fn:{}\" class=\"footnote-ref\">{}</a></sup>",
+            number, number
+        ),
+        None => escape(&format!("[^{}]", id), true),
+    }
+}
+
+fn format_footnote_definition(id: &str, content: &[Block], ctx: &Context) -> String {
+    if ctx.footnote_numbers.contains_key(&id.to_lowercase()) {
+        // referenced footnotes are rendered at the end of the document
+        return "".to_owned();
+    }
+
+    // fall back to the original markdown if the footnote is never referenced
+    let mut elements = vec![Text(format!("[^{}]: ", id))];
+    let rest = match content.split_first() {
+        Some((Paragraph(first), rest)) => {
+            elements.extend_from_slice(first);
+            rest
+        }
+        _ => content,
+    };
+    let mut ret = format_paragraph(&elements, ctx);
+    if !rest.is_empty() {
+        ret.push_str(&format_blocks(rest, ctx));
+        ret.push('\n');
+    }
+    ret
+}
+
+fn format_footnotes(ctx: &Context) -> String {
+    if ctx.footnote_numbers.is_empty() {
+        return "".to_owned();
+    }
+
+    let mut footnotes: Vec<(&String, &usize)> = ctx.footnote_numbers.iter().collect();
+    footnotes.sort_by_key(|&(_, number)| *number);
+
+    let mut ret = String::new();
+    for (id, number) in footnotes {
+        let content = format_blocks(ctx.footnote_definitions[id], ctx);
+        let backlink = format!(
+            "<a href=\"#fnref:{}\" class=\"footnote-backref\">↩</a>",
+            number
+        );
+        // put the back-reference into the last paragraph if there is one
+        let content = if content.ends_with("</p>\n") {
+            format!("{} {}</p>", &content[..content.len() - 5], backlink)
+        } else {
+            format!("{}<p>{}</p>", content, backlink)
+        };
+        ret.push_str(&format!("<li id=\"fn:{}\">\n{}\n</li>\n", number, content));
+    }
+    format!(
+        "\n<section class=\"footnotes\">\n<ol>\n{}</ol>\n</section>\n",
+        ret
+    )
+}
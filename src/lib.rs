@@ -17,14 +17,28 @@ use std::path::Path;
 
 mod html;
 mod markdown_generator;
+mod options;
 mod parser;
 
-pub use parser::{Block, ListItem, Span};
+pub use options::{
+    Directive, DirectiveHandler, DirectiveKind, IncludeLoader, MentionFilter, Options, QuoteStyle,
+    References, WikiLinkResolver,
+};
+pub use parser::{
+    AdmonitionKind, Alignment, Attributes, Block, FrontMatter, FrontMatterFormat, ListItem,
+    OrderedListDelimiter, OrderedListType, ReferenceKind, Span,
+};
 
 /// Converts a Markdown string to HTML
 pub fn to_html(text: &str) -> String {
     let result = parser::parse(text);
-    html::to_html(&result)
+    html::to_html(&result, &Options::default())
+}
+
+/// Converts a Markdown string to HTML, with the extensions enabled in `options`
+pub fn to_html_with_options(text: &str, options: &Options) -> String {
+    let result = parser::parse_with_options(text, options);
+    html::to_html(&result, options)
 }
 
 /// Converts a Markdown string to a tokenset of Markdown items
@@ -32,6 +46,18 @@ pub fn tokenize(text: &str) -> Vec<Block> {
     parser::parse(text)
 }
 
+/// Converts a Markdown string to a tokenset of Markdown items, with the
+/// extensions enabled in `options`
+pub fn tokenize_with_options(text: &str, options: &Options) -> Vec<Block> {
+    parser::parse_with_options(text, options)
+}
+
+/// Extracts the YAML (`---`) or TOML (`+++`) metadata block from the start
+/// of a Markdown string, if there is one
+pub fn front_matter(text: &str) -> Option<FrontMatter> {
+    parser::parse_front_matter(text)
+}
+
 /// Convert tokenset of Markdown items back to String
 pub fn generate_markdown(x: Vec<Block>) -> String {
     markdown_generator::generate(x)
@@ -45,5 +71,17 @@ pub fn file_to_html(path: &Path) -> io::Result<String> {
     file.read_to_string(&mut text)?;
 
     let result = parser::parse(&text);
-    Ok(html::to_html(&result))
+    Ok(html::to_html(&result, &Options::default()))
+}
+
+/// Opens a file and converts its contents to HTML, with the extensions
+/// enabled in `options`
+pub fn file_to_html_with_options(path: &Path, options: &Options) -> io::Result<String> {
+    let mut file = File::open(path)?;
+
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+
+    let result = parser::parse_file_with_options(&text, Some(path), options);
+    Ok(html::to_html(&result, options))
 }
@@ -9,5 +9,5 @@ fn main() {
     let path = Path::new(&args[1]);
     // let display = path.display();
 
-    println!("{}", markdown::file_to_html(&path).unwrap());
+    println!("{}", markdown::file_to_html(path).unwrap());
 }
@@ -1,4 +1,4 @@
-use super::{Block, ListItem, Span};
+use super::{Alignment, Attributes, Block, ListItem, OrderedListDelimiter, OrderedListType, Span};
 
 trait JoinHelper<I>
 where
@@ -19,25 +19,76 @@ where
 fn gen_block(b: Block) -> String {
     use Block::*;
     match b {
-        Header(s, level) => format!(
-            "{} {}",
-            ::std::iter::repeat("#".to_string()).take(level).j(""),
-            generate_from_spans(s)
+        FrontMatter(format, x) => {
+            if x.is_empty() {
+                format!("{0}\n{0}", format.delimiter())
+            } else {
+                format!("{0}\n{1}\n{0}", format.delimiter(), x)
+            }
+        }
+        Header(s, level, attributes) => format!(
+            "{} {}{}",
+            "#".repeat(level),
+            generate_from_spans(s),
+            generate_attributes(attributes, " ")
         ),
         Paragraph(s) => generate_from_spans(s),
         Blockquote(bb) => generate(bb).lines().map(|x| format!("> {}", x)).j("\n"),
-        CodeBlock(lang, x) => {
-            if lang.is_none() {
-                x.lines().map(|x| format!("    {}", x)).j("\n")
+        Admonition(kind, bb) => format!(
+            "> [!{}]{}",
+            kind.to_marker(),
+            generate(bb).lines().map(|x| format!("\n> {}", x)).j("")
+        ),
+        CodeBlock(lang, x, attributes) => {
+            if let Some(lang) = lang {
+                // the fence has to be longer than any backticks in the code
+                let longest = x.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                let fence = "`".repeat(longest.max(2) + 1);
+                format!(
+                    "{}{}{}\n{}\n{}",
+                    fence,
+                    lang,
+                    generate_attributes(attributes, " "),
+                    x,
+                    fence
+                )
             } else {
-                format!("```{}\n{}```", lang.unwrap(), x)
+                x.lines().map(|x| format!("    {}", x)).j("\n")
             }
         }
-        // [TODO]: Ordered list generation - 2017-12-10 10:12pm
-        OrderedList(_x, _num_type) => unimplemented!("Generate ordered list"),
+        DisplayMath(x) => format!("$$\n{}\n$$", x),
+        LeafDirective(name, label, attributes) => {
+            format!("::{}", generate_directive_head(name, label, attributes))
+        }
+        ContainerDirective(name, label, attributes, content) => format!(
+            ":::{}\n{}\n:::",
+            generate_directive_head(name, label, attributes),
+            generate(content)
+        ),
+        OrderedList(x, num_type, start, delimiter) => {
+            generate_from_ordered_li(x, num_type, start, delimiter)
+        }
         UnorderedList(x) => generate_from_li(x),
+        DefinitionList(entries) => generate_definition_list(entries),
+        Table(header, alignments, rows) => generate_table(header, alignments, rows),
+        AbbreviationDefinition(abbr, title) => {
+            format!("*[{}]: {}", abbr, title).trim_end().to_owned()
+        }
         LinkReference(id, url, None) => format!("[{}]: {}", id, url),
         LinkReference(id, url, Some(title)) => format!("[{}]: {} \"{}\"", id, url, title),
+        FootnoteDefinition(id, x) => format!(
+            "[^{}]: {}",
+            id,
+            generate(x)
+                .lines()
+                .enumerate()
+                .map(|(i, x)| if i == 0 || x.is_empty() {
+                    x.to_string()
+                } else {
+                    format!("    {}", x)
+                })
+                .j("\n")
+        ),
         Raw(x) => x,
         Hr => "===".to_owned(),
     }
@@ -46,47 +97,263 @@ fn gen_block(b: Block) -> String {
 fn gen_span(s: Span) -> String {
     use Span::*;
     match s {
-        Break => "  \n".to_string(),
+        Break => "\\\n".to_string(),
+        SoftBreak => "\n".to_string(),
         Text(x) => x,
         Literal(x) => format!("\\{}", x),
         Code(x) => format!("`{}`", x),
-        Link(a, b, None) => format!("[{}]({})", generate_from_spans(a), b),
-        Link(a, b, Some(c)) => format!("[{}]({} \"{}\")", generate_from_spans(a), b, c),
-        RefLink(_, _, raw) => raw,
-        Image(a, b, None) => format!("![{}]({})", a, b),
-        Image(a, b, Some(c)) => format!("![{}]({} \"{}\")", a, b, c),
+        InlineMath(x) => format!("${}$", x),
+        InlineDisplayMath(x) => format!("$${}$$", x),
+        Link(a, b, None, d) => format!(
+            "[{}]({}){}",
+            generate_from_spans(a),
+            b,
+            generate_attributes(d, "")
+        ),
+        Link(a, b, Some(c), d) => format!(
+            "[{}]({} \"{}\"){}",
+            generate_from_spans(a),
+            b,
+            c,
+            generate_attributes(d, "")
+        ),
+        RefLink(_, _, raw) | RefImage(_, _, raw) => raw,
+        AutoLink(_, _, raw) => raw,
+        Reference(_, text, _) => text,
+        Html(x) => x,
+        Image(a, b, None, d) => format!("![{}]({}){}", a, b, generate_attributes(d, "")),
+        Image(a, b, Some(c), d) => {
+            format!("![{}]({} \"{}\"){}", a, b, c, generate_attributes(d, ""))
+        }
+        WikiLink(page, None) => format!("[[{}]]", page),
+        WikiLink(page, Some(label)) => format!("[[{}|{}]]", page, label),
+        FootnoteReference(id) => format!("[^{}]", id),
         Emphasis(x) => format!("*{}*", generate_from_spans(x)),
         Strong(x) => format!("**{}**", generate_from_spans(x)),
+        Strikethrough(x) => format!("~~{}~~", generate_from_spans(x)),
+        Superscript(x) => format!("^{}^", generate_from_spans(x)),
+        Subscript(x) => format!("~{}~", generate_from_spans(x)),
+        Mark(x) => format!("=={}==", generate_from_spans(x)),
+        InlineDirective(name, label, attributes) => format!(
+            ":{}[{}]{}",
+            name,
+            generate_from_spans(label),
+            generate_attributes(attributes, "")
+        ),
     }
 }
 
-fn generate_from_li(data: Vec<ListItem>) -> String {
+// generates the name, label and attributes of a directive like `note[Label]{#id}`
+fn generate_directive_head(
+    name: String,
+    label: Vec<Span>,
+    attributes: Option<Attributes>,
+) -> String {
+    let label = if label.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", generate_from_spans(label))
+    };
+    format!("{}{}{}", name, label, generate_attributes(attributes, ""))
+}
+
+// generates an attribute list with the given separator before it
+fn generate_attributes(attributes: Option<Attributes>, sep: &str) -> String {
+    match attributes {
+        Some(attributes) => format!(
+            "{}{{{}}}",
+            sep,
+            attributes
+                .id
+                .into_iter()
+                .map(|id| format!("#{}", id))
+                .chain(attributes.classes.into_iter().map(|c| format!(".{}", c)))
+                .chain(
+                    attributes
+                        .pairs
+                        .into_iter()
+                        .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                )
+                .j(" ")
+        ),
+        None => String::new(),
+    }
+}
+
+// Items of tight lists, which have some simple items, are written without
+// blank lines, which would make all items paragraphs.
+fn generate_li_content(item: ListItem, tight: bool) -> String {
     use ListItem::*;
 
+    let (content, checked) = match item {
+        Simple(x, checked) => (generate_from_spans(x), checked),
+        Paragraph(x, checked) => (
+            format!(
+                "{}{}",
+                x.into_iter()
+                    .map(gen_block)
+                    .j(if tight { "\n" } else { "\n\n" })
+                    .lines()
+                    .enumerate()
+                    .map(|(i, x)| if i == 0 {
+                        x.to_string()
+                    } else {
+                        format!("    {}", x)
+                    })
+                    .j("\n"),
+                if tight { "" } else { "\n" }
+            ),
+            checked,
+        ),
+    };
+
+    match checked {
+        Some(true) => format!("[x] {}", content),
+        Some(false) => format!("[ ] {}", content),
+        None => content,
+    }
+}
+
+fn is_tight(data: &[ListItem]) -> bool {
+    data.iter().any(|x| matches!(x, ListItem::Simple(_, _)))
+}
+
+fn generate_from_li(data: Vec<ListItem>) -> String {
+    let tight = is_tight(&data);
     data.into_iter()
-        .map(|x| {
+        .map(|x| format!("* {}", generate_li_content(x, tight)))
+        .j("\n")
+}
+
+fn generate_from_ordered_li(
+    data: Vec<ListItem>,
+    num_type: OrderedListType,
+    start: usize,
+    delimiter: OrderedListDelimiter,
+) -> String {
+    let tight = is_tight(&data);
+    let count = data.len();
+    let letters = matches!(
+        num_type,
+        OrderedListType::Lowercase | OrderedListType::Uppercase
+    );
+    // only single letters are read as list markers, so lists that would need
+    // a marker like `aa` repeat their first marker, or use numbers if even
+    // that can't be written as a single letter
+    let (num_type, repeat) = if letters && start > 26 {
+        (OrderedListType::Numeric, false)
+    } else {
+        (num_type, letters && start + count - 1 > 26)
+    };
+    // a list that isn't numbered and has a single item needs two spaces after
+    // the marker, otherwise it is read as a sentence like `i. e. ...`
+    let space = if num_type != OrderedListType::Numeric && count == 1 {
+        "  "
+    } else {
+        " "
+    };
+    data.into_iter()
+        .enumerate()
+        .map(|(i, x)| {
             format!(
-                "* {}",
-                match x {
-                    Simple(x) => generate_from_spans(x),
-                    Paragraph(x) => format!(
-                        "{}\n",
-                        generate(x)
-                            .lines()
-                            .enumerate()
-                            .map(|(i, x)| if i == 0 {
-                                x.to_string()
-                            } else {
-                                format!("    {}", x)
-                            })
-                            .j("\n")
-                    ),
-                }
+                "{}{}{}{}",
+                num_type.format_number(if repeat { start } else { start + i }),
+                delimiter.to_char(),
+                space,
+                generate_li_content(x, tight)
             )
         })
         .j("\n")
 }
 
+fn generate_definition_list(entries: Vec<(Vec<Span>, Vec<Vec<Block>>)>) -> String {
+    let mut ret = String::new();
+    let mut prev_definitions = false;
+    for (term, definitions) in entries {
+        // terms sharing a definition are kept on consecutive lines
+        if prev_definitions {
+            ret.push_str("\n\n");
+        } else if !ret.is_empty() {
+            ret.push('\n');
+        }
+        prev_definitions = !definitions.is_empty();
+
+        ret.push_str(&generate_from_spans(term));
+        for definition in definitions {
+            ret.push_str("\n: ");
+            ret.push_str(
+                &generate(definition)
+                    .lines()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        if i == 0 || x.is_empty() {
+                            x.to_string()
+                        } else {
+                            format!("    {}", x)
+                        }
+                    })
+                    .j("\n"),
+            );
+        }
+    }
+    ret
+}
+
+fn generate_table(
+    header: Vec<Vec<Span>>,
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<Vec<Span>>>,
+) -> String {
+    let gen_row = |row: Vec<Vec<Span>>| {
+        row.into_iter()
+            .map(|cell| generate_from_spans(cell).replace('|', "\\|"))
+            .collect::<Vec<String>>()
+    };
+    let header = gen_row(header);
+    let rows = rows.into_iter().map(gen_row).collect::<Vec<_>>();
+
+    // every column is as wide as its widest cell, but at least three characters
+    let widths = (0..alignments.len())
+        .map(|i| {
+            ::std::iter::once(&header)
+                .chain(rows.iter())
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .fold(3, ::std::cmp::max)
+        })
+        .collect::<Vec<usize>>();
+
+    let gen_line = |cells: &[String]| {
+        let cells = cells
+            .iter()
+            .zip(&alignments)
+            .zip(&widths)
+            .map(|((cell, alignment), &width)| match *alignment {
+                Alignment::Right => format!("{:>1$}", cell, width),
+                Alignment::Center => format!("{:^1$}", cell, width),
+                Alignment::Left | Alignment::None => format!("{:<1$}", cell, width),
+            })
+            .j(" | ");
+        format!("| {} |", cells)
+    };
+
+    let delimiter = alignments
+        .iter()
+        .zip(&widths)
+        .map(|(alignment, &width)| match *alignment {
+            Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+            Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+            Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+            Alignment::None => "-".repeat(width),
+        })
+        .j(" | ");
+
+    ::std::iter::once(gen_line(&header))
+        .chain(::std::iter::once(format!("| {} |", delimiter)))
+        .chain(rows.iter().map(|row| gen_line(row)))
+        .j("\n")
+}
+
 fn generate_from_spans(data: Vec<Span>) -> String {
     data.into_iter().map(gen_span).j("")
 }
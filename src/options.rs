@@ -0,0 +1,295 @@
+use parser::Attributes;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Resolves the page names of wiki links like `[[Page Name]]` to urls.
+pub trait WikiLinkResolver {
+    /// Returns the url of the page with the given name.
+    fn url(&self, page: &str) -> String;
+
+    /// Returns whether the page exists. Links to missing pages are rendered
+    /// with `class="new"`.
+    fn exists(&self, _page: &str) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for dyn WikiLinkResolver + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("WikiLinkResolver")
+    }
+}
+
+// links to the page name with underscores for spaces, like MediaWiki does
+pub struct DefaultWikiLinkResolver;
+
+impl WikiLinkResolver for DefaultWikiLinkResolver {
+    fn url(&self, page: &str) -> String {
+        page.replace(' ', "_")
+    }
+}
+
+/// Loads the files included with `{{#include path}}`.
+pub trait IncludeLoader {
+    /// Returns the content of the file at the given path.
+    fn load(&self, path: &Path) -> io::Result<String>;
+}
+
+impl fmt::Debug for dyn IncludeLoader + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("IncludeLoader")
+    }
+}
+
+// reads the included files from the file system
+pub struct FileSystemLoader;
+
+impl IncludeLoader for FileSystemLoader {
+    fn load(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+}
+
+/// Where a directive was used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    /// Inside of text, like `:kbd[Ctrl]`
+    Inline,
+    /// On a line of its own, like `::youtube[Talk]{id=abc}`
+    Leaf,
+    /// Around other blocks, like `:::note` ... `:::`
+    Container,
+}
+
+/// A directive that is being rendered by a `DirectiveHandler`.
+#[derive(Debug)]
+pub struct Directive<'a> {
+    /// Where the directive was used
+    pub kind: DirectiveKind,
+    /// The name of the directive, e.g. `youtube`
+    pub name: &'a str,
+    /// The label in brackets, rendered to HTML
+    pub label: &'a str,
+    /// The attributes in braces
+    pub attributes: &'a Attributes,
+    /// The blocks inside of a container directive, rendered to HTML
+    pub content: &'a str,
+}
+
+/// Renders the directives with a certain name to HTML.
+///
+/// It is implemented for closures taking a `&Directive` and returning a
+/// `String`.
+pub trait DirectiveHandler {
+    /// Returns the HTML for the directive.
+    fn render(&self, directive: &Directive) -> String;
+}
+
+impl<F> DirectiveHandler for F
+where
+    F: Fn(&Directive) -> String,
+{
+    fn render(&self, directive: &Directive) -> String {
+        self(directive)
+    }
+}
+
+impl fmt::Debug for dyn DirectiveHandler + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("DirectiveHandler")
+    }
+}
+
+/// Decides which `@handle` mentions become links.
+///
+/// It is implemented for closures taking the handle without the `@` and
+/// returning a `bool`.
+pub trait MentionFilter {
+    /// Returns whether the user with the given handle exists.
+    fn accept(&self, handle: &str) -> bool;
+}
+
+impl<F> MentionFilter for F
+where
+    F: Fn(&str) -> bool,
+{
+    fn accept(&self, handle: &str) -> bool {
+        self(handle)
+    }
+}
+
+impl fmt::Debug for dyn MentionFilter + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("MentionFilter")
+    }
+}
+
+/// The urls of issue, commit and mention references like `#123`.
+///
+/// In the url templates `{repository}`, `{number}`, `{sha}` and `{user}` are
+/// replaced by the parts of the reference.
+#[derive(Debug, Clone)]
+pub struct References {
+    /// The repository of references without one, like `#123`, as `owner/repo`
+    pub repository: String,
+    /// The url of issues and pull requests like `#123` or `owner/repo#123`
+    pub issue_url: String,
+    /// The url of commits like `a5c3785` or `owner/repo@a5c3785`
+    pub commit_url: String,
+    /// The url of users like `@octocat`
+    pub mention_url: String,
+    /// Mentions of handles it rejects are left as text. All mentions become
+    /// links if it isn't set.
+    pub mention_filter: Option<Arc<dyn MentionFilter + Send + Sync>>,
+}
+
+impl References {
+    /// References to the given repository on GitHub, as `owner/repo`.
+    pub fn github(repository: &str) -> References {
+        References {
+            repository: repository.to_owned(),
+            issue_url: "https://github.com/{repository}/issues/{number}".to_owned(),
+            commit_url: "https://github.com/{repository}/commit/{sha}".to_owned(),
+            mention_url: "https://github.com/{user}".to_owned(),
+            mention_filter: None,
+        }
+    }
+}
+
+/// The quotation marks used by `smart_punctuation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteStyle {
+    /// Opening double quote
+    pub double_open: String,
+    /// Closing double quote
+    pub double_close: String,
+    /// Opening single quote
+    pub single_open: String,
+    /// Closing single quote
+    pub single_close: String,
+}
+
+impl QuoteStyle {
+    /// Creates a quote style from the double and single quotation marks.
+    pub fn new(double: (&str, &str), single: (&str, &str)) -> QuoteStyle {
+        QuoteStyle {
+            double_open: double.0.to_owned(),
+            double_close: double.1.to_owned(),
+            single_open: single.0.to_owned(),
+            single_close: single.1.to_owned(),
+        }
+    }
+
+    /// English quotes: “double” and ‘single’
+    pub fn english() -> QuoteStyle {
+        QuoteStyle::new(("\u{201c}", "\u{201d}"), ("\u{2018}", "\u{2019}"))
+    }
+
+    /// German quotes: „double“ and ‚single‘
+    pub fn german() -> QuoteStyle {
+        QuoteStyle::new(("\u{201e}", "\u{201c}"), ("\u{201a}", "\u{2018}"))
+    }
+
+    /// French guillemets with no-break spaces: « double » and ‹ single ›
+    pub fn french() -> QuoteStyle {
+        QuoteStyle::new(
+            ("\u{ab}\u{a0}", "\u{a0}\u{bb}"),
+            ("\u{2039}\u{a0}", "\u{a0}\u{203a}"),
+        )
+    }
+
+    /// Swiss guillemets without spaces: «double» and ‹single›
+    pub fn swiss() -> QuoteStyle {
+        QuoteStyle::new(("\u{ab}", "\u{bb}"), ("\u{2039}", "\u{203a}"))
+    }
+}
+
+impl Default for QuoteStyle {
+    fn default() -> QuoteStyle {
+        QuoteStyle::english()
+    }
+}
+
+/// Options for the optional extensions of the Markdown syntax.
+///
+/// All extensions are disabled by default.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Recognise bare `http://`, `https://` and `www.` links and email
+    /// addresses in text, like GitHub's extended autolinks.
+    pub extended_autolinks: bool,
+    /// Encode the addresses of `mailto:` links and email autolinks as a
+    /// random mix of decimal and hexadecimal character entities, which makes
+    /// them harder to harvest for spam bots.
+    pub obfuscate_emails: bool,
+    /// Seed for the randomisation of `obfuscate_emails`. The same seed always
+    /// produces the same output.
+    pub email_obfuscation_seed: u64,
+    /// Replace GitHub's emoji shortcodes like `:tada:` in text with the
+    /// Unicode emoji. Only a subset of GitHub's shortcodes is built in,
+    /// unknown shortcodes are left as text. Code is left untouched.
+    pub emoji_shortcodes: bool,
+    /// Additional shortcodes, without the colons, mapped to the url of an
+    /// image that is shown instead. They take precedence over the built-in
+    /// shortcodes and are only used if `emoji_shortcodes` is set.
+    pub custom_emoji: HashMap<String, String>,
+    /// Resolves wiki links like `[[Page Name|label]]`. Without a resolver
+    /// they link to the page name with spaces replaced by underscores.
+    pub wiki_link_resolver: Option<Arc<dyn WikiLinkResolver + Send + Sync>>,
+    /// Replace straight quotes with curly quotes and apostrophes, `--` and
+    /// `---` with en and em dashes and `...` with an ellipsis, like
+    /// SmartyPants. Code and urls are left untouched.
+    pub smart_punctuation: bool,
+    /// The quotation marks used by `smart_punctuation`, English by default.
+    pub quote_style: QuoteStyle,
+    /// Turn every newline inside of a paragraph into a line break, like
+    /// GitHub comments and chat systems do.
+    pub hard_breaks: bool,
+    /// Handlers for directives like `:::note` ... `:::`, by the name of the
+    /// directive. Directives without a handler are rendered as a `<div>` or
+    /// `<span>` with a `data-directive` attribute.
+    pub directives: HashMap<String, Arc<dyn DirectiveHandler + Send + Sync>>,
+    /// A paragraph consisting of only this marker is replaced by a table of
+    /// contents, `[TOC]` if it isn't set. A marker like `[TOC]` stays a link
+    /// if there is a link reference for it.
+    pub toc_marker: Option<String>,
+    /// The levels of the headers listed in the table of contents, all levels
+    /// if it isn't set.
+    pub toc_levels: Option<RangeInclusive<usize>>,
+    /// Turn issue references like `#123` or `owner/repo#123`, commit SHAs and
+    /// `@handle` mentions in text into links. Code and links are left
+    /// untouched.
+    pub references: Option<References>,
+    /// Replace paragraphs like `{{#include path.md}}` with the content of
+    /// the file. A line range like `path.md:3:10` or a section like
+    /// `path.md#install` include only a part of it. Markdown files are parsed,
+    /// other files become code blocks. Paths are relative to the including
+    /// file, includes that can't be resolved are left as text.
+    pub includes: bool,
+    /// Loads the included files, from the file system if it isn't set.
+    pub include_loader: Option<Arc<dyn IncludeLoader + Send + Sync>>,
+    /// The directory that included files must be inside of, the directory of
+    /// the document if it isn't set. Absolute paths can't be included, and
+    /// paths are checked without following symbolic links. Markdown that
+    /// isn't read from a file, like in `to_html_with_options`, only resolves
+    /// includes if this is set, relative to it.
+    pub include_root: Option<PathBuf>,
+    /// How deeply includes may be nested, 8 if it isn't set.
+    pub max_include_depth: Option<usize>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::Options;
+
+    #[test]
+    fn options_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Options>();
+    }
+}
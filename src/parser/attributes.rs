@@ -0,0 +1,134 @@
+use parser::Attributes;
+use regex::Regex;
+
+// parses an attribute list like `{#id .class key=value}` at the start of the text
+// and returns it along with its length
+pub fn parse_attributes(text: &str) -> Option<(Attributes, usize)> {
+    lazy_static! {
+        static ref ATTRIBUTE_LIST: Regex = Regex::new(
+            r#"^\{:?(?P<attributes>(?:\s*(?:[#.][^\s{}#.=]+|[A-Za-z_:][\w.:-]*=(?:"[^"]*"|'[^']*'|[^\s"'{}=]+)))+)\s*\}"#
+        )
+        .unwrap();
+        static ref ATTRIBUTE: Regex = Regex::new(
+            r#"(?:#(?P<id>[^\s{}#.=]+)|\.(?P<class>[^\s{}#.=]+)|(?P<key>[A-Za-z_:][\w.:-]*)=(?:"(?P<double>[^"]*)"|'(?P<single>[^']*)'|(?P<bare>[^\s"'{}=]+)))"#
+        )
+        .unwrap();
+    }
+
+    let caps = ATTRIBUTE_LIST.captures(text)?;
+    let mut attributes = Attributes::default();
+    for attribute in ATTRIBUTE.captures_iter(caps.name("attributes").unwrap().as_str()) {
+        if let Some(id) = attribute.name("id") {
+            attributes.id = Some(id.as_str().to_owned());
+        } else if let Some(class) = attribute.name("class") {
+            attributes.classes.push(class.as_str().to_owned());
+        } else {
+            let value = attribute
+                .name("double")
+                .or_else(|| attribute.name("single"))
+                .or_else(|| attribute.name("bare"))
+                .unwrap();
+            attributes.pairs.push((
+                attribute.name("key").unwrap().as_str().to_owned(),
+                value.as_str().to_owned(),
+            ));
+        }
+    }
+    Some((attributes, caps[0].len()))
+}
+
+// splits an attribute list from the end of the text,
+// e.g. from the content of a header
+pub fn split_trailing_attributes(text: &str) -> (&str, Option<Attributes>) {
+    let trimmed = text.trim_end();
+    if trimmed.ends_with('}') {
+        for (i, _) in trimmed.rmatch_indices('{') {
+            if let Some((attributes, len)) = parse_attributes(&trimmed[i..]) {
+                if i + len == trimmed.len() {
+                    return (trimmed[..i].trim_end(), Some(attributes));
+                }
+            }
+        }
+    }
+    (text, None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_attributes, split_trailing_attributes};
+    use parser::Attributes;
+
+    #[test]
+    fn finds_attributes() {
+        assert_eq!(
+            parse_attributes("{#install .tabbed .wide width=120 alt=\"a logo\" k='v'} test"),
+            Some((
+                Attributes {
+                    id: Some("install".to_owned()),
+                    classes: vec!["tabbed".to_owned(), "wide".to_owned()],
+                    pairs: vec![
+                        ("width".to_owned(), "120".to_owned()),
+                        ("alt".to_owned(), "a logo".to_owned()),
+                        ("k".to_owned(), "v".to_owned())
+                    ]
+                },
+                53
+            ))
+        );
+
+        assert_eq!(
+            parse_attributes("{: #id}"),
+            Some((
+                Attributes {
+                    id: Some("id".to_owned()),
+                    ..Default::default()
+                },
+                7
+            ))
+        );
+    }
+
+    #[test]
+    fn finds_trailing_attributes() {
+        assert_eq!(
+            split_trailing_attributes("Install {#install}  "),
+            (
+                "Install",
+                Some(Attributes {
+                    id: Some("install".to_owned()),
+                    ..Default::default()
+                })
+            )
+        );
+        assert_eq!(
+            split_trailing_attributes("Install {not} {.a}"),
+            (
+                "Install {not}",
+                Some(Attributes {
+                    classes: vec!["a".to_owned()],
+                    ..Default::default()
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_attributes("{}"), None);
+        assert_eq!(parse_attributes("{ }"), None);
+        assert_eq!(parse_attributes("{test}"), None);
+        assert_eq!(parse_attributes("{#id test}"), None);
+        assert_eq!(parse_attributes("{#id"), None);
+        assert_eq!(parse_attributes("{k=}"), None);
+        assert_eq!(split_trailing_attributes("Test {x}"), ("Test {x}", None));
+        assert_eq!(
+            split_trailing_attributes("Test {#a} b"),
+            ("Test {#a} b", None)
+        );
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_attributes("test {#id}"), None);
+    }
+}
@@ -0,0 +1,65 @@
+use parser::Block;
+use parser::Block::AbbreviationDefinition;
+use regex::Regex;
+
+pub fn parse_abbreviation_definition(lines: &[&str]) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref ABBREVIATION_DEFINITION: Regex =
+            Regex::new(r"^ {0,3}\*\[(?P<abbr>[^\[\]]+)\]:(?P<title>.*)$").unwrap();
+    }
+
+    let caps = ABBREVIATION_DEFINITION.captures(lines[0])?;
+    let abbr = caps.name("abbr").unwrap().as_str().trim();
+    if abbr.is_empty() {
+        return None;
+    }
+    Some((
+        AbbreviationDefinition(
+            abbr.to_owned(),
+            caps.name("title").unwrap().as_str().trim().to_owned(),
+        ),
+        1,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_abbreviation_definition;
+    use parser::Block::AbbreviationDefinition;
+
+    #[test]
+    fn finds_abbreviation_definition() {
+        assert_eq!(
+            parse_abbreviation_definition(&["*[HTML]: Hyper Text Markup Language", "test"]),
+            Some((
+                AbbreviationDefinition("HTML".to_owned(), "Hyper Text Markup Language".to_owned()),
+                1
+            ))
+        );
+
+        assert_eq!(
+            parse_abbreviation_definition(&["*[W3C]:"]),
+            Some((AbbreviationDefinition("W3C".to_owned(), "".to_owned()), 1))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_abbreviation_definition(&["*[]: Empty"]), None);
+        assert_eq!(parse_abbreviation_definition(&["*[ ]: Empty"]), None);
+        assert_eq!(
+            parse_abbreviation_definition(&["[HTML]: http://example.com"]),
+            None
+        );
+        assert_eq!(parse_abbreviation_definition(&["* [HTML]: a list"]), None);
+        assert_eq!(parse_abbreviation_definition(&["    *[HTML]: code"]), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(
+            parse_abbreviation_definition(&["test", "*[HTML]: Hyper Text Markup Language"]),
+            None
+        );
+    }
+}
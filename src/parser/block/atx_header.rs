@@ -1,9 +1,10 @@
+use parser::attributes::split_trailing_attributes;
 use parser::span::parse_spans;
 use parser::Block;
 use parser::Block::Header;
 use regex::Regex;
 
-pub fn parse_atx_header(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_atx_header(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
     lazy_static! {
         static ref ATX_HEADER_RE: Regex =
             Regex::new(r"^(?P<level>#{1,6})\s(?P<text>.*?)(?:\s#*)?$").unwrap();
@@ -11,10 +12,18 @@ pub fn parse_atx_header(lines: &[&str]) -> Option<(Block, usize)> {
 
     if ATX_HEADER_RE.is_match(lines[0]) {
         let caps = ATX_HEADER_RE.captures(lines[0]).unwrap();
+        let (text, attributes) = split_trailing_attributes(caps.name("text").unwrap().as_str());
+        // closing hashes may come before the attribute list
+        let text = if attributes.is_some() {
+            text.trim_end_matches('#').trim_end()
+        } else {
+            text
+        };
         return Some((
             Header(
-                parse_spans(caps.name("text").unwrap().as_str()),
+                parse_spans(text, extended_autolinks),
                 caps.name("level").unwrap().as_str().len(),
+                attributes,
             ),
             1,
         ));
@@ -25,30 +34,35 @@ pub fn parse_atx_header(lines: &[&str]) -> Option<(Block, usize)> {
 #[cfg(test)]
 mod test {
     use super::parse_atx_header;
+    use parser::Attributes;
     use parser::Block::Header;
     use parser::Span::Text;
 
     #[test]
     fn finds_atx_header() {
         assert_eq!(
-            parse_atx_header(&vec!["### Test", "testtest"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 3), 1)
+            parse_atx_header(&["### Test", "testtest"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 3, None), 1)
         );
 
         assert_eq!(
-            parse_atx_header(&vec!["# Test", "testtest"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 1), 1)
+            parse_atx_header(&["# Test", "testtest"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 1, None), 1)
         );
 
         assert_eq!(
-            parse_atx_header(&vec!["###### Test", "testtest"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 6), 1)
+            parse_atx_header(&["###### Test", "testtest"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 6, None), 1)
         );
 
         assert_eq!(
-            parse_atx_header(&vec!["### Test and a pretty long sentence", "testtest"]).unwrap(),
+            parse_atx_header(&["### Test and a pretty long sentence", "testtest"], false).unwrap(),
             (
-                Header(vec![Text("Test and a pretty long sentence".to_owned())], 3),
+                Header(
+                    vec![Text("Test and a pretty long sentence".to_owned())],
+                    3,
+                    None
+                ),
                 1
             )
         );
@@ -57,28 +71,74 @@ mod test {
     #[test]
     fn ignores_closing_hashes() {
         assert_eq!(
-            parse_atx_header(&vec!["### Test ###", "testtest"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 3), 1)
+            parse_atx_header(&["### Test ###", "testtest"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 3, None), 1)
         );
 
         assert_eq!(
-            parse_atx_header(&vec!["# Test #", "testtest"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 1), 1)
+            parse_atx_header(&["# Test #", "testtest"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 1, None), 1)
         );
 
         assert_eq!(
-            parse_atx_header(&vec!["###### Test ##", "testtest"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 6), 1)
+            parse_atx_header(&["###### Test ##", "testtest"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 6, None), 1)
         );
 
         assert_eq!(
-            parse_atx_header(&vec![
-                "### Test and a pretty long sentence #########",
-                "testtest"
-            ])
+            parse_atx_header(
+                &["### Test and a pretty long sentence #########", "testtest"],
+                false
+            )
             .unwrap(),
             (
-                Header(vec![Text("Test and a pretty long sentence".to_owned())], 3),
+                Header(
+                    vec![Text("Test and a pretty long sentence".to_owned())],
+                    3,
+                    None
+                ),
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn finds_attributes() {
+        assert_eq!(
+            parse_atx_header(&["## Install {#install .tabbed}"], false).unwrap(),
+            (
+                Header(
+                    vec![Text("Install".to_owned())],
+                    2,
+                    Some(Attributes {
+                        id: Some("install".to_owned()),
+                        classes: vec!["tabbed".to_owned()],
+                        pairs: vec![]
+                    })
+                ),
+                1
+            )
+        );
+
+        assert_eq!(
+            parse_atx_header(&["## Install ## {.tabbed}"], false).unwrap(),
+            (
+                Header(
+                    vec![Text("Install".to_owned())],
+                    2,
+                    Some(Attributes {
+                        classes: vec!["tabbed".to_owned()],
+                        ..Default::default()
+                    })
+                ),
+                1
+            )
+        );
+
+        assert_eq!(
+            parse_atx_header(&["## Install {not attributes}"], false).unwrap(),
+            (
+                Header(vec![Text("Install {not attributes}".to_owned())], 2, None),
                 1
             )
         );
@@ -86,8 +146,8 @@ mod test {
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_atx_header(&vec!["####### Test", "testtest"]), None);
-        assert_eq!(parse_atx_header(&vec!["Test #", "testtest"]), None);
-        assert_eq!(parse_atx_header(&vec!["T ### est #", "testtest"]), None);
+        assert_eq!(parse_atx_header(&["####### Test", "testtest"], false), None);
+        assert_eq!(parse_atx_header(&["Test #", "testtest"], false), None);
+        assert_eq!(parse_atx_header(&["T ### est #", "testtest"], false), None);
     }
 }
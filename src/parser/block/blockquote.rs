@@ -1,8 +1,9 @@
 use parser::block::parse_blocks;
-use parser::Block;
-use parser::Block::Blockquote;
+use parser::Block::{Admonition, Blockquote};
+use parser::{AdmonitionKind, Block};
+use regex::Regex;
 
-pub fn parse_blockquote(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_blockquote(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
     // if the first char isnt a blockquote don't even bother
     if lines[0].is_empty() || !lines[0].starts_with(">") {
         return None;
@@ -23,14 +24,17 @@ pub fn parse_blockquote(lines: &[&str]) -> Option<(Block, usize)> {
         // stop parsing on two newlines or if the paragraph after
         // a newline isn't started with a >
         // we continue to parse if it's just another empty line
-        if prev_newline && line.len() > 0 && !line.starts_with(">") {
+        if prev_newline && !line.is_empty() && !line.starts_with('>') {
             break;
         }
-        if line.is_empty() {
-            prev_newline = true;
-        } else {
-            prev_newline = false;
+        // an admonition marker after an empty line starts a new blockquote
+        if prev_newline
+            && i > 0
+            && parse_admonition_marker(line.trim_start_matches('>').trim_start()).is_some()
+        {
+            break;
         }
+        prev_newline = line.is_empty();
         let mut chars = line.chars();
         let begin = match chars.next() {
             Some('>') => match chars.next() {
@@ -47,47 +51,108 @@ pub fn parse_blockquote(lines: &[&str]) -> Option<(Block, usize)> {
     }
 
     if i > 0 {
-        return Some((Blockquote(parse_blocks(&content)), i));
+        if let Some((kind, content)) = parse_admonition_marker(&content) {
+            return Some((
+                Admonition(kind, parse_blocks(content, extended_autolinks)),
+                i,
+            ));
+        }
+        return Some((Blockquote(parse_blocks(&content, extended_autolinks)), i));
     }
 
     None
 }
 
+// recognises a GitHub-style `[!NOTE]` marker on the first line of a blockquote
+// and returns its kind along with the rest of the content
+fn parse_admonition_marker(content: &str) -> Option<(AdmonitionKind, &str)> {
+    lazy_static! {
+        static ref MARKER: Regex = Regex::new(r"^\[!(?P<kind>[A-Za-z]+)\] *(?:\n|$)").unwrap();
+    }
+
+    let caps = MARKER.captures(content)?;
+    let kind = AdmonitionKind::from_marker(caps.name("kind").unwrap().as_str())?;
+    Some((kind, &content[caps[0].len()..]))
+}
+
 #[cfg(test)]
 mod test {
     use super::parse_blockquote;
-    use parser::Block::Blockquote;
+    use parser::AdmonitionKind;
+    use parser::Block::{Admonition, Blockquote, Paragraph};
+    use parser::Span::{SoftBreak, Text};
 
     #[test]
     fn finds_blockquote() {
-        match parse_blockquote(&vec!["> A citation", "> is good"]) {
+        match parse_blockquote(&["> A citation", "> is good"], false) {
             Some((Blockquote(_), 2)) => (),
             _ => panic!(),
         }
 
-        match parse_blockquote(&vec!["> A citation", "> is good,", "very good"]) {
+        match parse_blockquote(&["> A citation", "> is good,", "very good"], false) {
             Some((Blockquote(_), 3)) => (),
             _ => panic!(),
         }
     }
 
+    #[test]
+    fn finds_admonition() {
+        assert_eq!(
+            parse_blockquote(&["> [!WARNING]", "> Check the backups", "> first"], false),
+            Some((
+                Admonition(
+                    AdmonitionKind::Warning,
+                    vec![Paragraph(vec![
+                        Text("Check the backups".to_owned()),
+                        SoftBreak,
+                        Text("first".to_owned())
+                    ])]
+                ),
+                3
+            ))
+        );
+
+        assert_eq!(
+            parse_blockquote(&["> [!note]"], false),
+            Some((Admonition(AdmonitionKind::Note, vec![]), 1))
+        );
+
+        // unknown kinds and markers followed by text stay blockquotes
+        match parse_blockquote(&["> [!FOO]"], false) {
+            Some((Blockquote(_), 1)) => (),
+            x => panic!("Found {:?}", x),
+        }
+        match parse_blockquote(&["> [!NOTE] text"], false) {
+            Some((Blockquote(_), 1)) => (),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
     #[test]
     fn knows_when_to_stop() {
-        match parse_blockquote(&vec!["> A citation", "> is good", "", "whatever"]) {
+        match parse_blockquote(&["> A citation", "> is good", "", "whatever"], false) {
             Some((Blockquote(_), 3)) => (),
             _ => panic!(),
         }
+
+        match parse_blockquote(&["> A citation", "", "> [!NOTE]", "> A note"], false) {
+            Some((Blockquote(_), 2)) => (),
+            x => panic!("Found {:?}", x),
+        }
     }
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_blockquote(&vec!["wat > this"]), None);
+        assert_eq!(parse_blockquote(&["wat > this"], false), None);
     }
 
     #[test]
     fn no_early_matching() {
         assert_eq!(
-            parse_blockquote(&vec!["Hello", "> A citation", "> is good", "", "whatever"]),
+            parse_blockquote(
+                &["Hello", "> A citation", "> is good", "", "whatever"],
+                false
+            ),
             None
         );
     }
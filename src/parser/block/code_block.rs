@@ -1,29 +1,30 @@
-use parser::Block;
+use parser::attributes::{parse_attributes, split_trailing_attributes};
 use parser::Block::CodeBlock;
+use parser::{Attributes, Block};
 use regex::Regex;
 
 pub fn parse_code_block(lines: &[&str]) -> Option<(Block, usize)> {
+    parse_fenced_code_block(lines).or_else(|| parse_indented_code_block(lines))
+}
+
+fn parse_indented_code_block(lines: &[&str]) -> Option<(Block, usize)> {
     lazy_static! {
         static ref CODE_BLOCK_SPACES: Regex = Regex::new(r"^ {4}").unwrap();
         static ref CODE_BLOCK_TABS: Regex = Regex::new(r"^\t").unwrap();
-        static ref CODE_BLOCK_BACKTICKS: Regex = Regex::new(r"^```").unwrap();
     }
 
     let mut content = String::new();
-    let mut lang: Option<String> = None;
     let mut line_number = 0;
-    let mut backtick_opened = false;
-    let mut backtick_closed = false;
 
     for line in lines {
-        if !backtick_opened && CODE_BLOCK_SPACES.is_match(line) {
+        if CODE_BLOCK_SPACES.is_match(line) {
             if line_number > 0 && !content.is_empty() {
                 content.push('\n');
             }
             // remove top-level spaces
             content.push_str(&line[4..line.len()]);
             line_number += 1;
-        } else if !backtick_opened && CODE_BLOCK_TABS.is_match(line) {
+        } else if CODE_BLOCK_TABS.is_match(line) {
             if line_number > 0 && !content.is_empty() {
                 content.push('\n');
             }
@@ -32,30 +33,15 @@ pub fn parse_code_block(lines: &[&str]) -> Option<(Block, usize)> {
                 // remove top-level spaces
                 content.push_str(&line[1..line.len()]);
             }
-            line_number += 1;
-        } else if CODE_BLOCK_BACKTICKS.is_match(line) {
-            line_number += 1;
-
-            if !backtick_opened && !(line_number == 0 && line.get(3..).is_some()) {
-                lang = Some(String::from(line.get(3..).unwrap()));
-                backtick_opened = true;
-            } else if backtick_opened {
-                backtick_closed = true;
-                break;
-            }
-        } else if backtick_opened {
-            content.push_str(line);
-            content.push('\n');
-
             line_number += 1;
         } else {
             break;
         }
     }
 
-    if line_number > 0 && ((backtick_opened && backtick_closed) || !backtick_opened) {
+    if line_number > 0 {
         return Some((
-            CodeBlock(lang, content.trim_matches('\n').to_owned()),
+            CodeBlock(None, content.trim_matches('\n').to_owned(), None),
             line_number,
         ));
     }
@@ -63,49 +49,240 @@ pub fn parse_code_block(lines: &[&str]) -> Option<(Block, usize)> {
     None
 }
 
+// Parses a code block fenced by at least three backticks or tildes, following
+// CommonMark: the closing fence has to be at least as long as the opening one
+// and a block that is never closed runs until the end of the document.
+pub fn parse_fenced_code_block(lines: &[&str]) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref OPENING_FENCE: Regex =
+            Regex::new(r"^(?P<indent> {0,3})(?P<fence>`{3,}|~{3,})(?P<info>.*)$").unwrap();
+        static ref CLOSING_FENCE: Regex =
+            Regex::new(r"^ {0,3}(?P<fence>`{3,}|~{3,})[ \t]*$").unwrap();
+    }
+
+    let caps = OPENING_FENCE.captures(lines.first()?)?;
+    let indent = caps["indent"].len();
+    let fence = &caps["fence"];
+    let info = &caps["info"];
+    // the info string of a backtick fence can't contain backticks,
+    // otherwise this might be inline code
+    if fence.starts_with('`') && info.contains('`') {
+        return None;
+    }
+
+    let mut content = String::new();
+    let mut line_number = 1;
+    for line in &lines[1..] {
+        line_number += 1;
+        if let Some(closing) = CLOSING_FENCE.captures(line) {
+            let closing = &closing["fence"];
+            if closing.starts_with(&fence[..1]) && closing.len() >= fence.len() {
+                break;
+            }
+        }
+        // remove as many spaces as the opening fence was indented
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        content.push_str(&line[spaces.min(indent)..]);
+        content.push('\n');
+    }
+
+    let (lang, attributes) = parse_info_string(info);
+    Some((
+        CodeBlock(
+            Some(lang),
+            content.trim_matches('\n').to_owned(),
+            attributes,
+        ),
+        line_number,
+    ))
+}
+
+// Splits the info string of a fence like `rust,ignore title="main.rs" {linenos=true}`
+// into the language and the attributes. Comma-separated words after the
+// language become classes, and `key=value` pairs may be given with or
+// without braces.
+fn parse_info_string(info: &str) -> (String, Option<Attributes>) {
+    let (info, braced) = split_trailing_attributes(info.trim());
+    let (first, rest) = match info.find(char::is_whitespace) {
+        Some(i) => (&info[..i], info[i..].trim()),
+        None => (info, ""),
+    };
+    let (lang, rest) = if first.contains('=') {
+        ("", info)
+    } else {
+        (first, rest)
+    };
+
+    let mut words = lang.split(',');
+    let lang = words.next().unwrap_or_default().to_owned();
+    let mut attributes = Attributes {
+        classes: words
+            .filter(|word| !word.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        ..Default::default()
+    };
+
+    for other in parse_attributes(&format!("{{{}}}", rest))
+        .map(|(attributes, _)| attributes)
+        .into_iter()
+        .chain(braced)
+    {
+        if other.id.is_some() {
+            attributes.id = other.id;
+        }
+        attributes.classes.extend(other.classes);
+        attributes.pairs.extend(other.pairs);
+    }
+
+    if attributes == Attributes::default() {
+        (lang, None)
+    } else {
+        (lang, Some(attributes))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::parse_code_block;
+    use parser::Attributes;
     use parser::Block::CodeBlock;
 
     #[test]
     fn finds_code_block() {
         assert_eq!(
-            parse_code_block(&vec!["    Test"]).unwrap(),
-            ((CodeBlock(None, "Test".to_owned()), 1))
+            parse_code_block(&["    Test"]).unwrap(),
+            (CodeBlock(None, "Test".to_owned(), None), 1)
         );
 
         assert_eq!(
-            parse_code_block(&vec!["    Test", "    this"]).unwrap(),
-            ((CodeBlock(None, "Test\nthis".to_owned()), 2))
+            parse_code_block(&["    Test", "    this"]).unwrap(),
+            (CodeBlock(None, "Test\nthis".to_owned(), None), 2)
         );
 
         assert_eq!(
-            parse_code_block(&vec!["```testlang", "Test", "this", "```"]).unwrap(),
-            ((
-                CodeBlock(Some(String::from("testlang")), "Test\nthis".to_owned()),
+            parse_code_block(&["```testlang", "Test", "this", "```"]).unwrap(),
+            (
+                CodeBlock(
+                    Some(String::from("testlang")),
+                    "Test\nthis".to_owned(),
+                    None
+                ),
                 4
-            ))
+            )
+        );
+    }
+
+    #[test]
+    fn finds_attributes() {
+        assert_eq!(
+            parse_code_block(&["```rust {#main .numbered}", "fn main() {}", "```"]).unwrap(),
+            (
+                CodeBlock(
+                    Some("rust".to_owned()),
+                    "fn main() {}".to_owned(),
+                    Some(Attributes {
+                        id: Some("main".to_owned()),
+                        classes: vec!["numbered".to_owned()],
+                        pairs: vec![]
+                    })
+                ),
+                3
+            )
+        );
+    }
+
+    #[test]
+    fn finds_commonmark_fences() {
+        assert_eq!(
+            parse_code_block(&["~~~", "a ``` b", "~~~~", "after"]).unwrap(),
+            (
+                CodeBlock(Some(String::new()), "a ``` b".to_owned(), None),
+                3
+            )
+        );
+
+        assert_eq!(
+            parse_code_block(&["````md", "```", "inner", "```", "````"]).unwrap(),
+            (
+                CodeBlock(Some("md".to_owned()), "```\ninner\n```".to_owned(), None),
+                5
+            )
+        );
+
+        assert_eq!(
+            parse_code_block(&["  ```", "   indented", " once", "  ```"]).unwrap(),
+            (
+                CodeBlock(Some(String::new()), " indented\nonce".to_owned(), None),
+                4
+            )
+        );
+
+        assert_eq!(
+            parse_code_block(&["```", "never", "", "closed"]).unwrap(),
+            (
+                CodeBlock(Some(String::new()), "never\n\nclosed".to_owned(), None),
+                4
+            )
+        );
+    }
+
+    #[test]
+    fn finds_info_strings() {
+        assert_eq!(
+            parse_code_block(&[
+                "```rust,ignore title=\"main.rs\" {linenos=true hl_lines=\"3-5\"}",
+                "fn main() {}",
+                "```"
+            ])
+            .unwrap(),
+            (
+                CodeBlock(
+                    Some("rust".to_owned()),
+                    "fn main() {}".to_owned(),
+                    Some(Attributes {
+                        id: None,
+                        classes: vec!["ignore".to_owned()],
+                        pairs: vec![
+                            ("title".to_owned(), "main.rs".to_owned()),
+                            ("linenos".to_owned(), "true".to_owned()),
+                            ("hl_lines".to_owned(), "3-5".to_owned())
+                        ]
+                    })
+                ),
+                3
+            )
+        );
+
+        assert_eq!(
+            parse_code_block(&["``` python extra words", "pass", "```"]).unwrap(),
+            (
+                CodeBlock(Some("python".to_owned()), "pass".to_owned(), None),
+                3
+            )
         );
     }
 
     #[test]
     fn knows_when_to_stop() {
         assert_eq!(
-            parse_code_block(&vec!["    Test", "    this", "stuff", "    now"]).unwrap(),
-            ((CodeBlock(None, "Test\nthis".to_owned()), 2))
+            parse_code_block(&["    Test", "    this", "stuff", "    now"]).unwrap(),
+            (CodeBlock(None, "Test\nthis".to_owned(), None), 2)
         );
     }
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_code_block(&vec!["   Test"]), None);
+        assert_eq!(parse_code_block(&["   Test"]), None);
+        assert_eq!(parse_code_block(&["``", "Test", "``"]), None);
+        assert_eq!(parse_code_block(&["```a`b", "Test", "```"]), None);
+        assert_eq!(parse_code_block(&["    ```", "Test", "```"]).unwrap().1, 1);
     }
 
     #[test]
     fn no_early_matching() {
         assert_eq!(
-            parse_code_block(&vec!["Test", "    this", "stuff", "    now"]),
+            parse_code_block(&["Test", "    this", "stuff", "    now"]),
             None
         );
     }
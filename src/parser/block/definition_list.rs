@@ -0,0 +1,200 @@
+use parser::block::parse_blocks;
+use parser::span::parse_spans;
+use parser::Block;
+use parser::Block::DefinitionList;
+use regex::Regex;
+
+lazy_static! {
+    static ref DEFINITION: Regex = Regex::new(r"^ {0,3}:(?: +|\t)(?P<content>.*)").unwrap();
+    static ref INDENTED: Regex = Regex::new(r"^(?: {4}|\t)(?P<content>.*)").unwrap();
+}
+
+pub fn parse_definition_list(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
+    let mut items = vec![];
+
+    // counts the number of parsed lines to return
+    let mut i = 0;
+
+    loop {
+        // one or more terms, each on its own line
+        let terms_start = i;
+        while i < lines.len() && is_term(lines[i]) {
+            i += 1;
+        }
+        let terms = &lines[terms_start..i];
+
+        // the terms are followed by a definition, optionally after an empty line
+        if i < lines.len() && lines[i].trim().is_empty() {
+            i += 1;
+        }
+        if terms.is_empty() || i >= lines.len() || !DEFINITION.is_match(lines[i]) {
+            i = terms_start;
+            break;
+        }
+
+        let mut definitions = vec![];
+        while i < lines.len() && DEFINITION.is_match(lines[i]) {
+            let caps = DEFINITION.captures(lines[i]).unwrap();
+            let mut content = caps.name("content").unwrap().as_str().to_owned();
+            i += 1;
+
+            // captures if the previous line was empty, meaning the definition
+            // ends unless the next line is indented
+            let mut prev_newline = false;
+
+            while i < lines.len() {
+                let line = lines[i];
+                if line.trim().is_empty() {
+                    prev_newline = true;
+                } else if let Some(caps) = INDENTED.captures(line) {
+                    content.push('\n');
+                    content.push_str(caps.name("content").unwrap().as_str());
+                    prev_newline = false;
+                } else if !prev_newline && !DEFINITION.is_match(line) {
+                    // lazy continuation of the current paragraph
+                    content.push('\n');
+                    content.push_str(line);
+                } else {
+                    break;
+                }
+
+                if prev_newline {
+                    content.push('\n');
+                }
+                i += 1;
+            }
+            definitions.push(parse_blocks(&content, extended_autolinks));
+        }
+
+        // all but the last term share the definitions of the last one
+        for term in &terms[..terms.len() - 1] {
+            items.push((parse_spans(term, extended_autolinks), vec![]));
+        }
+        items.push((
+            parse_spans(terms[terms.len() - 1], extended_autolinks),
+            definitions,
+        ));
+    }
+
+    if items.is_empty() {
+        return None;
+    }
+
+    Some((DefinitionList(items), i))
+}
+
+fn is_term(line: &str) -> bool {
+    !line.trim().is_empty() && !DEFINITION.is_match(line) && !INDENTED.is_match(line)
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_definition_list;
+    use parser::Block::{DefinitionList, Paragraph};
+    use parser::Span::{SoftBreak, Text};
+
+    #[test]
+    fn finds_definition_list() {
+        assert_eq!(
+            parse_definition_list(&["Apple", ": A fruit", ": A company"], false).unwrap(),
+            (
+                DefinitionList(vec![(
+                    vec![Text("Apple".to_owned())],
+                    vec![
+                        vec![Paragraph(vec![Text("A fruit".to_owned())])],
+                        vec![Paragraph(vec![Text("A company".to_owned())])]
+                    ]
+                )]),
+                3
+            )
+        );
+
+        assert_eq!(
+            parse_definition_list(
+                &[
+                    "Apple",
+                    "Orange",
+                    "",
+                    ":   Fruits",
+                    "",
+                    "Kale",
+                    ": A vegetable"
+                ],
+                false
+            )
+            .unwrap(),
+            (
+                DefinitionList(vec![
+                    (vec![Text("Apple".to_owned())], vec![]),
+                    (
+                        vec![Text("Orange".to_owned())],
+                        vec![vec![Paragraph(vec![Text("Fruits".to_owned())])]]
+                    ),
+                    (
+                        vec![Text("Kale".to_owned())],
+                        vec![vec![Paragraph(vec![Text("A vegetable".to_owned())])]]
+                    )
+                ]),
+                7
+            )
+        );
+    }
+
+    #[test]
+    fn finds_multi_paragraph_definitions() {
+        assert_eq!(
+            parse_definition_list(
+                &[
+                    "Term",
+                    ": A definition",
+                    "that goes on",
+                    "",
+                    "    and has another paragraph"
+                ],
+                false
+            )
+            .unwrap(),
+            (
+                DefinitionList(vec![(
+                    vec![Text("Term".to_owned())],
+                    vec![vec![
+                        Paragraph(vec![
+                            Text("A definition".to_owned()),
+                            SoftBreak,
+                            Text("that goes on".to_owned())
+                        ]),
+                        Paragraph(vec![Text("and has another paragraph".to_owned())])
+                    ]]
+                )]),
+                5
+            )
+        );
+    }
+
+    #[test]
+    fn knows_when_to_stop() {
+        match parse_definition_list(&["Term", ": Definition", "", "A paragraph"], false) {
+            Some((DefinitionList(_), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_definition_list(
+            &["Term", ": Definition", "", "Not a term", "", "Text"],
+            false,
+        ) {
+            Some((DefinitionList(_), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_definition_list(&["Term"], false), None);
+        assert_eq!(
+            parse_definition_list(&["Term", "", "", ": Definition"], false),
+            None
+        );
+        assert_eq!(parse_definition_list(&["Term", ":Definition"], false), None);
+        assert_eq!(parse_definition_list(&[": Definition"], false), None);
+    }
+}
@@ -0,0 +1,177 @@
+use parser::block::code_block::parse_fenced_code_block;
+use parser::block::parse_blocks;
+use parser::span::{parse_directive_head, parse_spans};
+use parser::Block;
+use parser::Block::{ContainerDirective, LeafDirective};
+use regex::Regex;
+
+pub fn parse_directive(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref DIRECTIVE_BEGIN: Regex = Regex::new(r"^(?P<colons>:{2,})(?P<head>.*)$").unwrap();
+        static ref DIRECTIVE_END: Regex = Regex::new(r"^(?P<colons>:{3,})\s*$").unwrap();
+    }
+
+    let caps = DIRECTIVE_BEGIN.captures(lines[0])?;
+    let colons = caps.name("colons").unwrap().as_str().len();
+    let head = caps.name("head").unwrap().as_str();
+    let (name, label, attributes, len) = parse_directive_head(head)?;
+    // nothing but whitespace may follow the directive
+    if !head[len..].trim().is_empty() {
+        return None;
+    }
+    let label = parse_spans(label.unwrap_or_default(), extended_autolinks);
+
+    if colons == 2 {
+        return Some((LeafDirective(name.to_owned(), label, attributes), 1));
+    }
+
+    // like fenced code, a container that is never closed runs until the end of
+    // the document. Nested containers with the same number of colons and
+    // fenced code are skipped, so that their end isn't taken for the end of
+    // this one.
+    let mut depth = 0;
+    let mut end = lines.len();
+    let mut i = 1;
+    while i < lines.len() {
+        if let Some((_, consumed_lines)) = parse_fenced_code_block(&lines[i..]) {
+            i += consumed_lines;
+            continue;
+        }
+        if let Some(caps) = DIRECTIVE_END.captures(lines[i]) {
+            if caps.name("colons").unwrap().as_str().len() >= colons {
+                if depth == 0 {
+                    end = i;
+                    break;
+                }
+                depth -= 1;
+            }
+        } else if let Some(caps) = DIRECTIVE_BEGIN.captures(lines[i]) {
+            if caps.name("colons").unwrap().as_str().len() == colons {
+                depth += 1;
+            }
+        }
+        i += 1;
+    }
+
+    let content = parse_blocks(&lines[1..end].join("\n"), extended_autolinks);
+    Some((
+        ContainerDirective(name.to_owned(), label, attributes, content),
+        (end + 1).min(lines.len()),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_directive;
+    use parser::Attributes;
+    use parser::Block::{CodeBlock, ContainerDirective, LeafDirective, Paragraph};
+    use parser::Span::{Emphasis, Text};
+
+    #[test]
+    fn finds_leaf_directive() {
+        assert_eq!(
+            parse_directive(&["::youtube[Talk]{id=abc}", "test"], false),
+            Some((
+                LeafDirective(
+                    "youtube".to_owned(),
+                    vec![Text("Talk".to_owned())],
+                    Some(Attributes {
+                        id: None,
+                        classes: vec![],
+                        pairs: vec![("id".to_owned(), "abc".to_owned())]
+                    })
+                ),
+                1
+            ))
+        );
+
+        assert_eq!(
+            parse_directive(&["::toc"], false),
+            Some((LeafDirective("toc".to_owned(), vec![], None), 1))
+        );
+    }
+
+    #[test]
+    fn finds_container_directive() {
+        assert_eq!(
+            parse_directive(
+                &[":::note{title=\"Heads up\"}", "Some *text*", ":::", "after"],
+                false
+            ),
+            Some((
+                ContainerDirective(
+                    "note".to_owned(),
+                    vec![],
+                    Some(Attributes {
+                        id: None,
+                        classes: vec![],
+                        pairs: vec![("title".to_owned(), "Heads up".to_owned())]
+                    }),
+                    vec![Paragraph(vec![
+                        Text("Some ".to_owned()),
+                        Emphasis(vec![Text("text".to_owned())])
+                    ])]
+                ),
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn finds_nested_containers() {
+        match parse_directive(
+            &[":::outer", ":::inner", "a", ":::", "b", ":::", "after"],
+            false,
+        ) {
+            Some((ContainerDirective(ref name, _, None, ref content), 6)) if name == "outer" => {
+                match content[0] {
+                    ContainerDirective(ref name, _, None, _) => assert_eq!(name, "inner"),
+                    ref x => panic!("Found {:?}", x),
+                }
+            }
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn skips_fenced_code() {
+        match parse_directive(&[":::a", "```", ":::", "```", ":::", "after"], false) {
+            Some((ContainerDirective(ref name, _, None, ref content), 5)) if name == "a" => {
+                assert_eq!(
+                    content,
+                    &vec![CodeBlock(Some("".to_owned()), ":::".to_owned(), None)]
+                )
+            }
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn knows_when_to_stop() {
+        assert_eq!(
+            parse_directive(&[":::note", "never closed"], false),
+            Some((
+                ContainerDirective(
+                    "note".to_owned(),
+                    vec![],
+                    None,
+                    vec![Paragraph(vec![Text("never closed".to_owned())])]
+                ),
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_directive(&[":note[a]"], false), None);
+        assert_eq!(parse_directive(&["::note[a] test"], false), None);
+        assert_eq!(parse_directive(&[":::"], false), None);
+        assert_eq!(parse_directive(&["::: note"], false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_directive(&["test ::note[a]"], false), None);
+    }
+}
@@ -0,0 +1,161 @@
+use parser::block::parse_blocks;
+use parser::Block;
+use parser::Block::FootnoteDefinition;
+use regex::Regex;
+
+pub fn parse_footnote_definition(
+    lines: &[&str],
+    extended_autolinks: bool,
+) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref FOOTNOTE_DEFINITION: Regex =
+            Regex::new(r"^\[\^(?P<id>[^\]\s]+)\]:\s*(?P<text>.*)").unwrap();
+        static ref INDENTED: Regex = Regex::new(r"^(?: {4}|\t)(?P<content>.*)").unwrap();
+    }
+
+    // if the beginning doesn't match a definition don't even bother
+    if !FOOTNOTE_DEFINITION.is_match(lines[0]) {
+        return None;
+    }
+
+    let caps = FOOTNOTE_DEFINITION.captures(lines[0]).unwrap();
+    let mut content = caps.name("text").unwrap().as_str().to_owned();
+
+    // counts the number of parsed lines to return
+    let mut i = 1;
+
+    // captures if the previous line was empty, meaning the definition
+    // ends unless the next line is indented
+    let mut prev_newline = false;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty() {
+            prev_newline = true;
+        } else if let Some(caps) = INDENTED.captures(line) {
+            // indented lines continue the definition, even after an empty line
+            content.push('\n');
+            content.push_str(caps.name("content").unwrap().as_str());
+            prev_newline = false;
+        } else if !prev_newline && !FOOTNOTE_DEFINITION.is_match(line) {
+            // lazy continuation of the current paragraph
+            content.push('\n');
+            content.push_str(line.trim_start());
+        } else {
+            break;
+        }
+
+        if prev_newline {
+            content.push('\n');
+        }
+        i += 1;
+    }
+
+    Some((
+        FootnoteDefinition(
+            caps.name("id").unwrap().as_str().to_owned(),
+            parse_blocks(&content, extended_autolinks),
+        ),
+        i,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_footnote_definition;
+    use parser::Block::{CodeBlock, FootnoteDefinition, Paragraph};
+    use parser::Span::{SoftBreak, Text};
+
+    #[test]
+    fn finds_footnote_definition() {
+        assert_eq!(
+            parse_footnote_definition(&["[^1]: A note"], false).unwrap(),
+            (
+                FootnoteDefinition(
+                    "1".to_owned(),
+                    vec![Paragraph(vec![Text("A note".to_owned())])]
+                ),
+                1
+            )
+        );
+
+        assert_eq!(
+            parse_footnote_definition(&["[^note]: A note", "that goes on"], false).unwrap(),
+            (
+                FootnoteDefinition(
+                    "note".to_owned(),
+                    vec![Paragraph(vec![
+                        Text("A note".to_owned()),
+                        SoftBreak,
+                        Text("that goes on".to_owned())
+                    ])]
+                ),
+                2
+            )
+        );
+    }
+
+    #[test]
+    fn finds_multi_paragraph_definition() {
+        assert_eq!(
+            parse_footnote_definition(
+                &[
+                    "[^note]: A note",
+                    "",
+                    "    Another paragraph",
+                    "",
+                    "        code",
+                    "",
+                    "Not in the note"
+                ],
+                false
+            )
+            .unwrap(),
+            (
+                FootnoteDefinition(
+                    "note".to_owned(),
+                    vec![
+                        Paragraph(vec![Text("A note".to_owned())]),
+                        Paragraph(vec![Text("Another paragraph".to_owned())]),
+                        CodeBlock(None, "code".to_owned(), None)
+                    ]
+                ),
+                6
+            )
+        );
+    }
+
+    #[test]
+    fn knows_when_to_stop() {
+        match parse_footnote_definition(&["[^1]: A note", "[^2]: Another note"], false) {
+            Some((FootnoteDefinition(_, _), 1)) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_footnote_definition(&["[^1]: A note", "", "Not in the note"], false) {
+            Some((FootnoteDefinition(_, _), 2)) => (),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(
+            parse_footnote_definition(&["[1]: https://example.com"], false),
+            None
+        );
+        assert_eq!(parse_footnote_definition(&["[^1] A note"], false), None);
+        assert_eq!(
+            parse_footnote_definition(&["[^a note]: A note"], false),
+            None
+        );
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(
+            parse_footnote_definition(&["Test", "[^1]: A note"], false),
+            None
+        );
+    }
+}
@@ -20,33 +20,33 @@ mod test {
 
     #[test]
     fn finds_hr() {
-        assert_eq!(parse_hr(&vec!["-------"]).unwrap(), (Hr, 1));
-        assert_eq!(parse_hr(&vec!["---"]).unwrap(), (Hr, 1));
+        assert_eq!(parse_hr(&["-------"]).unwrap(), (Hr, 1));
+        assert_eq!(parse_hr(&["---"]).unwrap(), (Hr, 1));
         assert_eq!(
-            parse_hr(&vec!["----------------------------"]).unwrap(),
+            parse_hr(&["----------------------------"]).unwrap(),
             (Hr, 1)
         );
-        assert_eq!(parse_hr(&vec!["-------", "abc"]).unwrap(), (Hr, 1));
+        assert_eq!(parse_hr(&["-------", "abc"]).unwrap(), (Hr, 1));
 
-        assert_eq!(parse_hr(&vec!["======="]).unwrap(), (Hr, 1));
-        assert_eq!(parse_hr(&vec!["==="]).unwrap(), (Hr, 1));
+        assert_eq!(parse_hr(&["======="]).unwrap(), (Hr, 1));
+        assert_eq!(parse_hr(&["==="]).unwrap(), (Hr, 1));
         assert_eq!(
-            parse_hr(&vec!["============================"]).unwrap(),
+            parse_hr(&["============================"]).unwrap(),
             (Hr, 1)
         );
-        assert_eq!(parse_hr(&vec!["=======", "abc"]).unwrap(), (Hr, 1));
+        assert_eq!(parse_hr(&["=======", "abc"]).unwrap(), (Hr, 1));
     }
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_hr(&vec!["a-------"]), None);
-        assert_eq!(parse_hr(&vec!["--- a"]), None);
-        assert_eq!(parse_hr(&vec!["--a-"]), None);
-        assert_eq!(parse_hr(&vec!["-------====--------------"]), None);
+        assert_eq!(parse_hr(&["a-------"]), None);
+        assert_eq!(parse_hr(&["--- a"]), None);
+        assert_eq!(parse_hr(&["--a-"]), None);
+        assert_eq!(parse_hr(&["-------====--------------"]), None);
 
-        assert_eq!(parse_hr(&vec!["a======"]), None);
-        assert_eq!(parse_hr(&vec!["=== a"]), None);
-        assert_eq!(parse_hr(&vec!["==a="]), None);
-        assert_eq!(parse_hr(&vec!["=======---================="]), None);
+        assert_eq!(parse_hr(&["a======"]), None);
+        assert_eq!(parse_hr(&["=== a"]), None);
+        assert_eq!(parse_hr(&["==a="]), None);
+        assert_eq!(parse_hr(&["=======---================="]), None);
     }
 }
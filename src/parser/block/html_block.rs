@@ -0,0 +1,184 @@
+use parser::Block;
+use parser::Block::Raw;
+use regex::Regex;
+
+// Recognizes the first six kinds of HTML blocks defined by CommonMark,
+// see https://spec.commonmark.org/0.30/#html-blocks
+pub fn parse_html_block(lines: &[&str]) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref RAW_TEXT_START: Regex =
+            Regex::new(r"(?i)^ {0,3}<(?:script|pre|style|textarea)(?:\s|>|$)").unwrap();
+        static ref RAW_TEXT_END: Regex =
+            Regex::new(r"(?i)</(?:script|pre|style|textarea)>").unwrap();
+        static ref COMMENT_START: Regex = Regex::new(r"^ {0,3}<!--").unwrap();
+        static ref COMMENT_END: Regex = Regex::new(r"-->").unwrap();
+        static ref PROCESSING_INSTRUCTION_START: Regex = Regex::new(r"^ {0,3}<\?").unwrap();
+        static ref PROCESSING_INSTRUCTION_END: Regex = Regex::new(r"\?>").unwrap();
+        static ref DECLARATION_START: Regex = Regex::new(r"^ {0,3}<![A-Za-z]").unwrap();
+        static ref DECLARATION_END: Regex = Regex::new(r">").unwrap();
+        static ref CDATA_START: Regex = Regex::new(r"^ {0,3}<!\[CDATA\[").unwrap();
+        static ref CDATA_END: Regex = Regex::new(r"\]\]>").unwrap();
+        static ref BLOCK_TAG: Regex = Regex::new(
+            r"(?i)^ {0,3}</?(?:address|article|aside|base|basefont|blockquote|body|caption|center|col|colgroup|dd|details|dialog|dir|div|dl|dt|fieldset|figcaption|figure|footer|form|frame|frameset|h1|h2|h3|h4|h5|h6|head|header|hr|html|iframe|legend|li|link|main|menu|menuitem|nav|noframes|ol|optgroup|option|p|param|search|section|summary|table|tbody|td|tfoot|th|thead|title|tr|track|ul)(?:\s|/?>|$)"
+        )
+        .unwrap();
+    }
+
+    let starts_and_ends: [(&Regex, &Regex); 5] = [
+        (&RAW_TEXT_START, &RAW_TEXT_END),
+        (&COMMENT_START, &COMMENT_END),
+        (&PROCESSING_INSTRUCTION_START, &PROCESSING_INSTRUCTION_END),
+        (&DECLARATION_START, &DECLARATION_END),
+        (&CDATA_START, &CDATA_END),
+    ];
+    for &(start, end) in starts_and_ends.iter() {
+        if start.is_match(lines[0]) {
+            // the block ends with the line that contains the end condition
+            let len = lines
+                .iter()
+                .position(|line| end.is_match(line))
+                .map_or(lines.len(), |i| i + 1);
+            return Some((Raw(lines[..len].join("\n")), len));
+        }
+    }
+
+    if BLOCK_TAG.is_match(lines[0]) {
+        return Some(parse_until_blank_line(lines));
+    }
+
+    None
+}
+
+// Recognizes HTML blocks that start with a line consisting of a single open
+// or closing tag. These can not interrupt a paragraph.
+pub fn parse_html_tag_block(lines: &[&str]) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref TAG_LINE: Regex = Regex::new(
+            r#"^ {0,3}(?:<(?P<open>[A-Za-z][A-Za-z0-9-]*)(?:\s+[a-zA-Z_:][a-zA-Z0-9_.:-]*(?:\s*=\s*(?:[^\s"'=<>`]+|'[^']*'|"[^"]*"))?)*\s*/?>|</(?P<close>[A-Za-z][A-Za-z0-9-]*)\s*>)\s*$"#
+        )
+        .unwrap();
+    }
+
+    if let Some(caps) = TAG_LINE.captures(lines[0]) {
+        let name = caps
+            .name("open")
+            .or_else(|| caps.name("close"))
+            .unwrap()
+            .as_str()
+            .to_lowercase();
+        if !["script", "style", "pre", "textarea"].contains(&name.as_str()) {
+            return Some(parse_until_blank_line(lines));
+        }
+    }
+    None
+}
+
+fn parse_until_blank_line(lines: &[&str]) -> (Block, usize) {
+    let len = lines
+        .iter()
+        .position(|line| line.trim().is_empty())
+        .unwrap_or(lines.len());
+    (Raw(lines[..len].join("\n")), len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_html_block, parse_html_tag_block};
+    use parser::Block::Raw;
+
+    #[test]
+    fn finds_raw_text_block() {
+        assert_eq!(
+            parse_html_block(&["<pre>", "", "  *code*", "</pre>", "text"]).unwrap(),
+            (Raw("<pre>\n\n  *code*\n</pre>".to_owned()), 4)
+        );
+
+        assert_eq!(
+            parse_html_block(&["<SCRIPT type=\"text/javascript\">a < b</script>", "text"]).unwrap(),
+            (
+                Raw("<SCRIPT type=\"text/javascript\">a < b</script>".to_owned()),
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn finds_comment() {
+        assert_eq!(
+            parse_html_block(&["<!-- a comment -->", "text"]).unwrap(),
+            (Raw("<!-- a comment -->".to_owned()), 1)
+        );
+
+        assert_eq!(
+            parse_html_block(&["<!--", "", "a comment", "", "-->", "text"]).unwrap(),
+            (Raw("<!--\n\na comment\n\n-->".to_owned()), 5)
+        );
+    }
+
+    #[test]
+    fn finds_declarations_and_instructions() {
+        assert_eq!(
+            parse_html_block(&["<!DOCTYPE html>", "text"]).unwrap(),
+            (Raw("<!DOCTYPE html>".to_owned()), 1)
+        );
+
+        assert_eq!(
+            parse_html_block(&["<?php", "echo 1;", "?>", "text"]).unwrap(),
+            (Raw("<?php\necho 1;\n?>".to_owned()), 3)
+        );
+
+        assert_eq!(
+            parse_html_block(&["<![CDATA[", "a", "]]>", "text"]).unwrap(),
+            (Raw("<![CDATA[\na\n]]>".to_owned()), 3)
+        );
+    }
+
+    #[test]
+    fn finds_block_tags() {
+        assert_eq!(
+            parse_html_block(&["<details>", "<summary>More</summary>", "", "text"]).unwrap(),
+            (Raw("<details>\n<summary>More</summary>".to_owned()), 2)
+        );
+
+        assert_eq!(
+            parse_html_block(&["<div class=\"note\">*text*", "</div>"]).unwrap(),
+            (Raw("<div class=\"note\">*text*\n</div>".to_owned()), 2)
+        );
+
+        assert_eq!(
+            parse_html_block(&["</DIV>", "", "text"]).unwrap(),
+            (Raw("</DIV>".to_owned()), 1)
+        );
+    }
+
+    #[test]
+    fn finds_tag_block() {
+        assert_eq!(
+            parse_html_tag_block(&["<custom-element a=\"1\" b='2' c>", "text", "", "more"])
+                .unwrap(),
+            (Raw("<custom-element a=\"1\" b='2' c>\ntext".to_owned()), 2)
+        );
+
+        assert_eq!(
+            parse_html_tag_block(&["</span>  "]).unwrap(),
+            (Raw("</span>  ".to_owned()), 1)
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_html_block(&["<span>text</span>"]), None);
+        assert_eq!(parse_html_block(&["<divider>"]), None);
+        assert_eq!(parse_html_block(&["    <div>"]), None);
+        assert_eq!(parse_html_block(&["<https://example.com>"]), None);
+        assert_eq!(parse_html_tag_block(&["<span>text</span>"]), None);
+        assert_eq!(parse_html_tag_block(&["<pre>"]), None);
+        assert_eq!(parse_html_tag_block(&["<a href=\"x\" <b>"]), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_html_block(&["text", "<div>"]), None);
+        assert_eq!(parse_html_tag_block(&["text", "<span>"]), None);
+    }
+}
@@ -53,7 +53,7 @@ mod test {
     #[test]
     fn finds_link_reference() {
         assert_eq!(
-            parse_link_reference(&vec!["[Test]: https://example.com"]).unwrap(),
+            parse_link_reference(&["[Test]: https://example.com"]).unwrap(),
             (
                 LinkReference("test".to_owned(), "https://example.com".to_owned(), None),
                 1
@@ -61,7 +61,7 @@ mod test {
         );
 
         assert_eq!(
-            parse_link_reference(&vec!["[Test]: https://example.com \"example\""]).unwrap(),
+            parse_link_reference(&["[Test]: https://example.com \"example\""]).unwrap(),
             (
                 LinkReference(
                     "test".to_owned(),
@@ -73,7 +73,7 @@ mod test {
         );
 
         assert_eq!(
-            parse_link_reference(&vec!["[Test]: https://example.com (example)"]).unwrap(),
+            parse_link_reference(&["[Test]: https://example.com (example)"]).unwrap(),
             (
                 LinkReference(
                     "test".to_owned(),
@@ -85,7 +85,7 @@ mod test {
         );
 
         assert_eq!(
-            parse_link_reference(&vec!["[Test]: https://example.com 'example'"]).unwrap(),
+            parse_link_reference(&["[Test]: https://example.com 'example'"]).unwrap(),
             (
                 LinkReference(
                     "test".to_owned(),
@@ -97,8 +97,7 @@ mod test {
         );
 
         assert_eq!(
-            parse_link_reference(&vec!["[Test]:     https://example.com        'example'"])
-                .unwrap(),
+            parse_link_reference(&["[Test]:     https://example.com        'example'"]).unwrap(),
             (
                 LinkReference(
                     "test".to_owned(),
@@ -110,7 +109,7 @@ mod test {
         );
 
         assert_eq!(
-            parse_link_reference(&vec!["[Test]:", "https://example.com \"example\""]).unwrap(),
+            parse_link_reference(&["[Test]:", "https://example.com \"example\""]).unwrap(),
             (
                 LinkReference(
                     "test".to_owned(),
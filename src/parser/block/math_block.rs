@@ -0,0 +1,93 @@
+use parser::Block;
+use parser::Block::DisplayMath;
+use regex::Regex;
+
+pub fn parse_math_block(lines: &[&str]) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref DOLLARS_BEGIN: Regex = Regex::new(r"^ {0,3}\$\$(?P<content>.*)$").unwrap();
+        static ref FENCE_BEGIN: Regex = Regex::new(r"^ {0,3}```math\s*$").unwrap();
+        static ref FENCE_END: Regex = Regex::new(r"^ {0,3}```\s*$").unwrap();
+    }
+
+    if let Some(caps) = DOLLARS_BEGIN.captures(lines[0]) {
+        let first = caps.name("content").unwrap().as_str().trim();
+
+        // the whole formula is on a single line
+        if let Some(formula) = first.strip_suffix("$$") {
+            if !formula.is_empty() {
+                return Some((DisplayMath(formula.trim().to_owned()), 1));
+            }
+        }
+
+        // like in LaTeX, a formula can't contain a blank line. Stopping there
+        // keeps a stray `$$` from swallowing the rest of the document.
+        let mut content = vec![first];
+        for (i, line) in lines.iter().enumerate().skip(1) {
+            let line = line.trim_end();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(last) = line.strip_suffix("$$") {
+                content.push(last);
+                return Some((DisplayMath(content.join("\n").trim().to_owned()), i + 1));
+            }
+            content.push(line);
+        }
+    } else if FENCE_BEGIN.is_match(lines[0]) {
+        for (i, line) in lines.iter().enumerate().skip(1) {
+            if FENCE_END.is_match(line) {
+                return Some((DisplayMath(lines[1..i].join("\n")), i + 1));
+            }
+        }
+    }
+
+    // unclosed math blocks are left to the other parsers
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_math_block;
+    use parser::Block::DisplayMath;
+
+    #[test]
+    fn finds_math_block() {
+        assert_eq!(
+            parse_math_block(&["$$", "x_1 = \\frac{a*b}{2}", "$$", "test"]),
+            Some((DisplayMath("x_1 = \\frac{a*b}{2}".to_owned()), 3))
+        );
+
+        assert_eq!(
+            parse_math_block(&["$$ e = mc^2 $$", "test"]),
+            Some((DisplayMath("e = mc^2".to_owned()), 1))
+        );
+
+        assert_eq!(
+            parse_math_block(&["$$a +", "  b$$"]),
+            Some((DisplayMath("a +\n  b".to_owned()), 2))
+        );
+
+        assert_eq!(
+            parse_math_block(&["```math", "a_1", "a_2", "```", "test"]),
+            Some((DisplayMath("a_1\na_2".to_owned()), 4))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_math_block(&["$$", "x"]), None);
+        assert_eq!(parse_math_block(&["$$x"]), None);
+        assert_eq!(
+            parse_math_block(&["$$ is a lot", "", "more text", "costs $$"]),
+            None
+        );
+        assert_eq!(parse_math_block(&["```math", "x"]), None);
+        assert_eq!(parse_math_block(&["```rust", "x", "```"]), None);
+        assert_eq!(parse_math_block(&["    $$x$$"]), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_math_block(&["test", "$$x$$"]), None);
+    }
+}
@@ -1,38 +1,63 @@
 use parser::span::parse_spans;
 use parser::Block;
 use parser::Block::Paragraph;
-use parser::Span::{Break, Text};
+use parser::Span;
+use parser::Span::{Break, SoftBreak, Text};
+use regex::Regex;
 
+mod abbreviation_definition;
 mod atx_header;
 mod blockquote;
 mod code_block;
+mod definition_list;
+mod directive;
+mod footnote_definition;
 mod hr;
+mod html_block;
 mod link_reference;
+mod math_block;
 mod ordered_list;
 mod setext_header;
+mod table;
 mod unordered_list;
+use self::abbreviation_definition::parse_abbreviation_definition;
 use self::atx_header::parse_atx_header;
 use self::blockquote::parse_blockquote;
 use self::code_block::parse_code_block;
+use self::definition_list::parse_definition_list;
+use self::directive::parse_directive;
+use self::footnote_definition::parse_footnote_definition;
 use self::hr::parse_hr;
+use self::html_block::{parse_html_block, parse_html_tag_block};
 use self::link_reference::parse_link_reference;
+use self::math_block::parse_math_block;
 use self::ordered_list::parse_ordered_list;
 use self::setext_header::parse_setext_header;
+use self::table::parse_table;
 use self::unordered_list::parse_unordered_list;
 
-pub fn parse_blocks(md: &str) -> Vec<Block> {
+pub fn parse_blocks(md: &str, extended_autolinks: bool) -> Vec<Block> {
     let mut blocks = vec![];
     let mut t = vec![];
+    // whether the last line of the paragraph ended with a backslash break
+    let mut backslash = false;
     let lines: Vec<&str> = md.lines().collect();
     let mut i = 0;
     while i < lines.len() {
-        match parse_block(&lines[i..lines.len()]) {
+        let block = parse_block(&lines[i..lines.len()], extended_autolinks).or_else(|| {
+            if t.is_empty() {
+                parse_paragraph_start(&lines[i..lines.len()], extended_autolinks)
+            } else {
+                None
+            }
+        });
+        match block {
             // if a block is found
             Some((block, consumed_lines)) => {
                 // the current paragraph has ended,
                 // push it to our blocks
                 if !t.is_empty() {
-                    blocks.push(Paragraph(t));
+                    blocks.push(end_paragraph(t, backslash));
                     t = Vec::new();
                 }
                 blocks.push(block);
@@ -42,11 +67,11 @@ pub fn parse_blocks(md: &str) -> Vec<Block> {
             None => {
                 // empty linebreak => new paragraph
                 if lines[i].is_empty() && !t.is_empty() {
-                    blocks.push(Paragraph(t));
+                    blocks.push(end_paragraph(t, backslash));
                     t = Vec::new();
                 }
 
-                let spans = parse_spans(lines[i]);
+                let spans = parse_spans(lines[i], extended_autolinks);
 
                 // add a newline between linebreaks
                 // except when we have a break element or nothing
@@ -54,95 +79,234 @@ pub fn parse_blocks(md: &str) -> Vec<Block> {
                     (Some(&Break), _) => {}
                     (_, None) => {}
                     (None, _) => {}
-                    _ => t.push(Text("\n".to_owned())),
+                    _ => t.push(SoftBreak),
                 }
 
+                backslash = lines[i].ends_with('\\') && spans.last() == Some(&Break);
                 t.extend_from_slice(&spans);
                 i += 1;
             }
         }
     }
     if !t.is_empty() {
-        blocks.push(Paragraph(t));
+        blocks.push(end_paragraph(t, backslash));
     }
     blocks
 }
 
-fn parse_block(lines: &[&str]) -> Option<(Block, usize)> {
+// A backslash at the end of the last line of a paragraph has no line to
+// break, so it is kept as text.
+fn end_paragraph(mut spans: Vec<Span>, backslash: bool) -> Block {
+    if backslash {
+        spans.pop();
+        match spans.last_mut() {
+            Some(&mut Text(ref mut text)) => text.push('\\'),
+            _ => spans.push(Text("\\".to_owned())),
+        }
+    }
+    Paragraph(spans)
+}
+
+// strips a task list marker (`[ ]` or `[x]`) from the start of a list item
+// and returns whether the item was checked
+pub fn parse_task_marker(content: &str) -> (Option<bool>, &str) {
+    lazy_static! {
+        static ref TASK_MARKER: Regex = Regex::new(r"^\[(?P<mark>[ xX])\](?: +|$)").unwrap();
+    }
+
+    if let Some(caps) = TASK_MARKER.captures(content) {
+        let checked = caps.name("mark").unwrap().as_str() != " ";
+        return (Some(checked), &content[caps[0].len()..]);
+    }
+    (None, content)
+}
+
+// Blocks that can't interrupt a paragraph, they are only tried if no
+// other block matches.
+fn parse_paragraph_start(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
+    pipe_opt!(
+    lines
+    => (parse_definition_list(extended_autolinks))
+    => parse_html_tag_block
+    )
+}
+
+fn parse_block(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
     pipe_opt!(
     lines
     => parse_hr
-    => parse_atx_header
+    => (parse_atx_header(extended_autolinks))
+    => parse_math_block
+    => (parse_directive(extended_autolinks))
     => parse_code_block
-    => parse_blockquote
-    => parse_unordered_list
-    => parse_ordered_list
+    => parse_html_block
+    => (parse_blockquote(extended_autolinks))
+    => (parse_unordered_list(extended_autolinks))
+    => (parse_ordered_list(extended_autolinks))
+    => (parse_footnote_definition(extended_autolinks))
     => parse_link_reference
+    => parse_abbreviation_definition
+    => (parse_table(extended_autolinks))
     // Must not match before anything else. See: https://spec.commonmark.org/0.29/#setext-headings
-    => parse_setext_header
+    => (parse_setext_header(extended_autolinks))
     )
 }
 
 #[cfg(test)]
 mod test {
     use super::parse_blocks;
-    use parser::Block::{Blockquote, CodeBlock, Header, Hr, Paragraph};
-    use parser::Span::Text;
+    use parser::Alignment;
+    use parser::Block::{Blockquote, CodeBlock, DefinitionList, Header, Hr, Paragraph, Raw, Table};
+    use parser::Span::{Break, Html, SoftBreak, Text};
 
     #[test]
     fn finds_atx_header() {
         assert_eq!(
-            parse_blocks("### Test"),
-            vec![Header(vec![Text("Test".to_owned())], 3)]
+            parse_blocks("### Test", false),
+            vec![Header(vec![Text("Test".to_owned())], 3, None)]
         );
     }
 
     #[test]
     fn finds_setext_header() {
         assert_eq!(
-            parse_blocks("Test\n-------"),
-            vec![Header(vec![Text("Test".to_owned())], 2)]
+            parse_blocks("Test\n-------", false),
+            vec![Header(vec![Text("Test".to_owned())], 2, None)]
         );
         assert_eq!(
-            parse_blocks("Test\n======="),
-            vec![Header(vec![Text("Test".to_owned())], 1)]
+            parse_blocks("Test\n=======", false),
+            vec![Header(vec![Text("Test".to_owned())], 1, None)]
         );
     }
 
     #[test]
     fn finds_hr() {
-        assert_eq!(parse_blocks("-------"), vec![Hr]);
-        assert_eq!(parse_blocks("======="), vec![Hr]);
+        assert_eq!(parse_blocks("-------", false), vec![Hr]);
+        assert_eq!(parse_blocks("=======", false), vec![Hr]);
+    }
+
+    #[test]
+    fn finds_line_breaks() {
+        assert_eq!(
+            parse_blocks("soft\nhard  \nbackslash\\\nend", false),
+            vec![Paragraph(vec![
+                Text("soft".to_owned()),
+                SoftBreak,
+                Text("hard".to_owned()),
+                Break,
+                Text("backslash".to_owned()),
+                Break,
+                Text("end".to_owned())
+            ])]
+        );
+        assert_eq!(
+            parse_blocks("end of paragraph\\\n\nnext\\\n# Header", false),
+            vec![
+                Paragraph(vec![Text("end of paragraph\\".to_owned())]),
+                Paragraph(vec![Text("next\\".to_owned())]),
+                Header(vec![Text("Header".to_owned())], 1, None)
+            ]
+        );
     }
 
     #[test]
     fn finds_code_block() {
         assert_eq!(
-            parse_blocks("    this is code\n    and this as well"),
-            vec![CodeBlock(None, "this is code\nand this as well".to_owned())]
+            parse_blocks("    this is code\n    and this as well", false),
+            vec![CodeBlock(
+                None,
+                "this is code\nand this as well".to_owned(),
+                None
+            )]
         );
 
         assert_eq!(
-            parse_blocks("```\nthis is code\nand this as well\n```"),
+            parse_blocks("```\nthis is code\nand this as well\n```", false),
             vec![CodeBlock(
                 Some(String::new()),
-                "this is code\nand this as well".to_owned()
+                "this is code\nand this as well".to_owned(),
+                None
             )]
         );
     }
 
+    #[test]
+    fn finds_html_blocks() {
+        assert_eq!(
+            parse_blocks("<div>\n*hi*\n</div>\n\nTest", false),
+            vec![
+                Raw("<div>\n*hi*\n</div>".to_owned()),
+                Paragraph(vec![Text("Test".to_owned())])
+            ]
+        );
+
+        assert_eq!(
+            parse_blocks("<custom>\nTest\n\nTest", false),
+            vec![
+                Raw("<custom>\nTest".to_owned()),
+                Paragraph(vec![Text("Test".to_owned())])
+            ]
+        );
+
+        // an arbitrary tag can't interrupt a paragraph
+        assert_eq!(
+            parse_blocks("Test\n<custom>", false),
+            vec![Paragraph(vec![
+                Text("Test".to_owned()),
+                SoftBreak,
+                Html("<custom>".to_owned())
+            ])]
+        );
+    }
+
+    #[test]
+    fn finds_table() {
+        assert_eq!(
+            parse_blocks("Test\n\n| a | b |\n|:--|--:|\n| c | d |\nTest", false),
+            vec![
+                Paragraph(vec![Text("Test".to_owned())]),
+                Table(
+                    vec![vec![Text("a".to_owned())], vec![Text("b".to_owned())]],
+                    vec![Alignment::Left, Alignment::Right],
+                    vec![vec![vec![Text("c".to_owned())], vec![Text("d".to_owned())]]]
+                ),
+                Paragraph(vec![Text("Test".to_owned())])
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_definition_list() {
+        assert_eq!(
+            parse_blocks("Term\n: Definition", false),
+            vec![DefinitionList(vec![(
+                vec![Text("Term".to_owned())],
+                vec![vec![Paragraph(vec![Text("Definition".to_owned())])]]
+            )])]
+        );
+
+        // a definition list doesn't steal setext headers
+        assert_eq!(
+            parse_blocks("Test\n====\n: Definition", false),
+            vec![
+                Header(vec![Text("Test".to_owned())], 1, None),
+                Paragraph(vec![Text(": Definition".to_owned())])
+            ]
+        );
+    }
+
     #[test]
     fn finds_blockquotes() {
         assert_eq!(
-            parse_blocks("> One Paragraph\n>\n> ## H2 \n>\n"),
+            parse_blocks("> One Paragraph\n>\n> ## H2 \n>\n", false),
             vec![Blockquote(vec![
                 Paragraph(vec![Text("One Paragraph".to_owned())]),
-                Header(vec![Text("H2".to_owned())], 2)
+                Header(vec![Text("H2".to_owned())], 2, None)
             ])]
         );
 
         assert_eq!(
-            parse_blocks("> One Paragraph\n>\n> > Another blockquote\n>\n"),
+            parse_blocks("> One Paragraph\n>\n> > Another blockquote\n>\n", false),
             vec![Blockquote(vec![
                 Paragraph(vec![Text("One Paragraph".to_owned())]),
                 Blockquote(vec![Paragraph(vec![Text("Another blockquote".to_owned())])])
@@ -150,7 +314,7 @@ mod test {
         );
 
         assert_eq!(
-            parse_blocks("> > One Paragraph\n> >\n> > Another blockquote\n>\n"),
+            parse_blocks("> > One Paragraph\n> >\n> > Another blockquote\n>\n", false),
             vec![Blockquote(vec![Blockquote(vec![
                 Paragraph(vec![Text("One Paragraph".to_owned())]),
                 Paragraph(vec![Text("Another blockquote".to_owned())])
@@ -158,14 +322,14 @@ mod test {
         );
 
         assert_eq!(
-            parse_blocks("> One Paragraph, just > text \n>\n"),
+            parse_blocks("> One Paragraph, just > text \n>\n", false),
             vec![Blockquote(vec![Paragraph(vec![Text(
                 "One Paragraph, just > text".to_owned()
             )])])]
         );
 
         assert_eq!(
-            parse_blocks("> One Paragraph\n>\n> just > text \n>\n"),
+            parse_blocks("> One Paragraph\n>\n> just > text \n>\n", false),
             vec![Blockquote(vec![
                 Paragraph(vec![Text("One Paragraph".to_owned())]),
                 Paragraph(vec![Text("just > text".to_owned())])
@@ -1,21 +1,29 @@
-use parser::block::parse_blocks;
+use parser::block::{parse_blocks, parse_task_marker};
 use parser::Block;
 use parser::Block::{OrderedList, Paragraph};
-use parser::{ListItem, OrderedListType};
-use regex::Regex;
+use parser::{to_roman, ListItem, OrderedListDelimiter, OrderedListType};
+use regex::{Captures, Regex};
 
-pub fn parse_ordered_list(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_ordered_list(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
     lazy_static! {
-        static ref LIST_BEGIN: Regex =
-            Regex::new(r"^(?P<indent> *)(?P<numbering>[0-9.]+|[aAiI]+\.) (?P<content>.*)").unwrap();
+        static ref LIST_BEGIN: Regex = Regex::new(
+            r"^(?P<indent> *)(?:(?P<number>[0-9]{1,9})(?:\.[0-9]+)*|(?P<letters>[a-zA-Z]|[ivxlcdm]+|[IVXLCDM]+))(?P<delimiter>[.)]) (?P<content>.*)"
+        )
+        .unwrap();
         static ref NEW_PARAGRAPH: Regex = Regex::new(r"^ +").unwrap();
         static ref INDENTED: Regex = Regex::new(r"^ {0,4}(?P<content>.*)").unwrap();
     }
 
     // if the beginning doesn't match a list don't even bother
-    if !LIST_BEGIN.is_match(lines[0]) {
-        return None;
-    }
+    let caps = LIST_BEGIN.captures(lines[0])?;
+    // the first item decides the numbering, start and delimiter of the list
+    let (num_type, start) = parse_number(&caps, None)?;
+    let delimiter = parse_delimiter(&caps);
+    // like in Pandoc, a single letter like `v.` needs two spaces after it or
+    // a second item to start a list, so that sentences starting with initials
+    // or abbreviations like `J. R. R.`, `v. Smith` or `i. e.` aren't lists
+    let needs_more_items = caps.name("letters").is_some_and(|l| l.as_str().len() == 1)
+        && !caps.name("content").unwrap().as_str().starts_with(' ');
 
     // a vec holding the contents and indentation
     // of each list item
@@ -28,11 +36,11 @@ pub fn parse_ordered_list(lines: &[&str]) -> Option<(Block, usize)> {
 
     let mut line_iter = lines.iter();
     let mut line = line_iter.next();
-    let mut list_num_opt = None;
 
     // loop for list items
-    loop {
-        if line.is_none() || !LIST_BEGIN.is_match(line.unwrap()) {
+    while let Some(caps) = line.and_then(|line| LIST_BEGIN.captures(line)) {
+        // a different numbering or delimiter starts a new list
+        if parse_number(&caps, Some(&num_type)).is_none() || parse_delimiter(&caps) != delimiter {
             break;
         }
         if prev_newline {
@@ -40,14 +48,9 @@ pub fn parse_ordered_list(lines: &[&str]) -> Option<(Block, usize)> {
             prev_newline = false;
         }
 
-        let caps = LIST_BEGIN.captures(line.unwrap()).unwrap();
-
-        let mut content = caps.name("content").unwrap().as_str().to_owned();
+        let (checked, content) = parse_task_marker(caps.name("content").unwrap().as_str());
+        let mut content = content.to_owned();
         let last_indent = caps.name("indent").unwrap().as_str().len();
-        //We use the first list type found
-        // TODO: utf-8 safe?
-        list_num_opt = list_num_opt
-            .or_else(|| Some(caps.name("numbering").unwrap().as_str()[0..1].to_owned()));
         i += 1;
 
         // parse additional lines of the listitem
@@ -67,109 +70,281 @@ pub fn parse_ordered_list(lines: &[&str]) -> Option<(Block, usize)> {
             }
 
             // newline means we start a new paragraph
-            if line.unwrap().is_empty() {
-                prev_newline = true;
-            } else {
-                prev_newline = false;
-            }
+            prev_newline = line.unwrap().is_empty();
 
             content.push('\n');
             let caps = INDENTED.captures(line.unwrap()).unwrap();
-            content.push_str(&caps.name("content").unwrap().as_str());
+            content.push_str(caps.name("content").unwrap().as_str());
 
             i += 1;
         }
-        contents.push(parse_blocks(&content));
+        contents.push((parse_blocks(&content, extended_autolinks), checked));
+    }
+
+    if needs_more_items && contents.len() < 2 {
+        return None;
     }
 
     let mut list_contents = vec![];
 
-    for c in contents {
+    for (c, checked) in contents {
         if is_paragraph || c.len() > 1 {
-            list_contents.push(ListItem::Paragraph(c));
+            list_contents.push(ListItem::Paragraph(c, checked));
+        } else if c.is_empty() {
+            list_contents.push(ListItem::Simple(vec![], checked));
         } else if let Paragraph(content) = c[0].clone() {
-            list_contents.push(ListItem::Simple(content));
+            list_contents.push(ListItem::Simple(content, checked));
         }
     }
 
     if i > 0 {
-        let list_num = list_num_opt.unwrap_or("1".to_string());
-        return Some((
-            OrderedList(list_contents, OrderedListType::from_str(&list_num)),
-            i,
-        ));
+        return Some((OrderedList(list_contents, num_type, start, delimiter), i));
     }
 
     None
 }
 
+// Returns the numbering and value of a list marker like `7`, `c` or `iv`.
+// Within a list, markers are read in the numbering of the first one,
+// e.g. `i` is 9 in a list starting at `a` but 1 in a list starting at `i`.
+fn parse_number(
+    caps: &Captures,
+    num_type: Option<&OrderedListType>,
+) -> Option<(OrderedListType, usize)> {
+    if let Some(number) = caps.name("number") {
+        return match num_type {
+            None | Some(&OrderedListType::Numeric) => {
+                Some((OrderedListType::Numeric, number.as_str().parse().unwrap()))
+            }
+            _ => None,
+        };
+    }
+
+    let letters = caps.name("letters").unwrap().as_str();
+    let (alphabetic, roman) = if letters.chars().all(|c| c.is_ascii_uppercase()) {
+        (OrderedListType::Uppercase, OrderedListType::UppercaseRoman)
+    } else {
+        (OrderedListType::Lowercase, OrderedListType::LowercaseRoman)
+    };
+    let letter_value = match letters.as_bytes() {
+        [letter] => Some((letter.to_ascii_lowercase() - b'a') as usize + 1),
+        _ => None,
+    };
+    let roman_value = from_roman(letters);
+
+    match num_type {
+        None if letters.eq_ignore_ascii_case("i") => roman_value.map(|n| (roman, n)),
+        None => letter_value
+            .map(|n| (alphabetic.clone(), n))
+            .or_else(|| roman_value.map(|n| (roman, n))),
+        Some(t) if *t == alphabetic => letter_value.map(|n| (alphabetic, n)),
+        Some(t) if *t == roman => roman_value.map(|n| (roman, n)),
+        _ => None,
+    }
+}
+
+fn parse_delimiter(caps: &Captures) -> OrderedListDelimiter {
+    match &caps["delimiter"] {
+        ")" => OrderedListDelimiter::Parenthesis,
+        _ => OrderedListDelimiter::Period,
+    }
+}
+
+// parses a roman numeral, which has to be written in the shortest form
+fn from_roman(roman: &str) -> Option<usize> {
+    let roman = roman.to_lowercase();
+    let values = roman
+        .chars()
+        .map(|c| match c {
+            'i' => Some(1),
+            'v' => Some(5),
+            'x' => Some(10),
+            'l' => Some(50),
+            'c' => Some(100),
+            'd' => Some(500),
+            'm' => Some(1000),
+            _ => None,
+        })
+        .collect::<Option<Vec<usize>>>()?;
+
+    let mut n = 0;
+    for (i, &value) in values.iter().enumerate() {
+        if values.get(i + 1).is_some_and(|&next| next > value) {
+            n -= value as isize;
+        } else {
+            n += value as isize;
+        }
+    }
+    if n > 0 && to_roman(n as usize) == roman {
+        Some(n as usize)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::parse_ordered_list;
     use parser::Block::OrderedList;
-    use parser::ListItem::Paragraph;
-    use parser::OrderedListType;
+    use parser::ListItem::{Paragraph, Simple};
+    use parser::Span::Text;
+    use parser::{OrderedListDelimiter, OrderedListType};
 
     #[test]
     fn finds_list() {
-        match parse_ordered_list(&vec!["1. A list", "2. is good"]) {
-            Some((OrderedList(_, lt), 2)) if lt == OrderedListType::Numeric => (),
+        match parse_ordered_list(&["1. A list", "2. is good"], false) {
+            Some((OrderedList(_, OrderedListType::Numeric, _, _), 2)) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_ordered_list(&["a. A list", "b. is good", "laksjdnflakdsjnf"], false) {
+            Some((OrderedList(_, OrderedListType::Lowercase, _, _), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_ordered_list(&["A. A list", "B. is good", "laksjdnflakdsjnf"], false) {
+            Some((OrderedList(_, OrderedListType::Uppercase, _, _), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn finds_task_list() {
+        assert_eq!(
+            parse_ordered_list(&["1. [ ] todo", "2. [x] done", "3. no task"], false),
+            Some((
+                OrderedList(
+                    vec![
+                        Simple(vec![Text("todo".to_owned())], Some(false)),
+                        Simple(vec![Text("done".to_owned())], Some(true)),
+                        Simple(vec![Text("no task".to_owned())], None)
+                    ],
+                    OrderedListType::Numeric,
+                    1,
+                    OrderedListDelimiter::Period
+                ),
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn finds_start_and_delimiter() {
+        assert_eq!(
+            parse_ordered_list(&["7) seven", "8) eight"], false),
+            Some((
+                OrderedList(
+                    vec![
+                        Simple(vec![Text("seven".to_owned())], None),
+                        Simple(vec![Text("eight".to_owned())], None)
+                    ],
+                    OrderedListType::Numeric,
+                    7,
+                    OrderedListDelimiter::Parenthesis
+                ),
+                2
+            ))
+        );
+
+        match parse_ordered_list(&["c. A list", "d. is good"], false) {
+            Some((
+                OrderedList(_, OrderedListType::Lowercase, 3, OrderedListDelimiter::Period),
+                2,
+            )) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_ordered_list(&["iv) A list", "v) is good"], false) {
+            Some((
+                OrderedList(
+                    _,
+                    OrderedListType::LowercaseRoman,
+                    4,
+                    OrderedListDelimiter::Parenthesis,
+                ),
+                2,
+            )) => (),
             x => panic!("Found {:?}", x),
         }
 
-        match parse_ordered_list(&vec!["a. A list", "b. is good", "laksjdnflakdsjnf"]) {
-            Some((OrderedList(_, lt), 3)) if lt == OrderedListType::Lowercase => (),
+        match parse_ordered_list(&["h. A list", "i. is good"], false) {
+            Some((OrderedList(ref items, OrderedListType::Lowercase, 8, _), 2)) => {
+                assert_eq!(items.len(), 2)
+            }
             x => panic!("Found {:?}", x),
         }
 
-        match parse_ordered_list(&vec!["A. A list", "B. is good", "laksjdnflakdsjnf"]) {
-            Some((OrderedList(_, lt), 3)) if lt == OrderedListType::Uppercase => (),
+        match parse_ordered_list(&["XII.  A list"], false) {
+            Some((OrderedList(_, OrderedListType::UppercaseRoman, 12, _), 1)) => (),
             x => panic!("Found {:?}", x),
         }
     }
 
     #[test]
     fn knows_when_to_stop() {
-        match parse_ordered_list(&vec!["i. A list", "ii. is good", "", "laksjdnflakdsjnf"]) {
-            Some((OrderedList(_, lt), 3)) if lt == OrderedListType::LowercaseRoman => (),
+        match parse_ordered_list(&["1. A list", "2) another list"], false) {
+            Some((OrderedList(ref items, _, _, _), 1)) if items.len() == 1 => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_ordered_list(&["1. A list", "b. another list"], false) {
+            Some((OrderedList(ref items, _, _, _), 1)) if items.len() == 1 => (),
             x => panic!("Found {:?}", x),
         }
 
-        match parse_ordered_list(&vec!["I. A list", "", "laksjdnflakdsjnf"]) {
-            Some((OrderedList(_, lt), 2)) if lt == OrderedListType::UppercaseRoman => (),
+        match parse_ordered_list(&["i. A list", "ii. is good", "", "laksjdnflakdsjnf"], false) {
+            Some((OrderedList(_, OrderedListType::LowercaseRoman, _, _), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_ordered_list(&["I.  A list", "", "laksjdnflakdsjnf"], false) {
+            Some((OrderedList(_, OrderedListType::UppercaseRoman, _, _), 2)) => (),
             x => panic!("Found {:?}", x),
         }
     }
 
     #[test]
     fn multi_level_list() {
-        match parse_ordered_list(&vec![
-            "1. A list",
-            "     1.1. One point one",
-            "     1.2. One point two",
-        ]) {
-            Some((OrderedList(ref items, lt), 3)) if lt == OrderedListType::Numeric => {
-                match &items[0] {
-                    &Paragraph(ref items) => match &items[1] {
-                        &OrderedList(_, ref lt1) if lt1 == &OrderedListType::Numeric => (),
-                        x => panic!("Found {:?}", x),
-                    },
+        match parse_ordered_list(
+            &[
+                "1. A list",
+                "     1.1. One point one",
+                "     1.2. One point two",
+            ],
+            false,
+        ) {
+            Some((OrderedList(ref items, OrderedListType::Numeric, _, _), 3)) => match &items[0] {
+                Paragraph(items, None) => match &items[1] {
+                    OrderedList(_, lt1, _, _) if lt1 == &OrderedListType::Numeric => (),
                     x => panic!("Found {:?}", x),
-                }
-            }
+                },
+                x => panic!("Found {:?}", x),
+            },
             x => panic!("Found {:?}", x),
         }
     }
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_ordered_list(&vec!["test 1. test"]), None);
+        assert_eq!(parse_ordered_list(&["test 1. test"], false), None);
+        assert_eq!(parse_ordered_list(&["J. R. R. Tolkien"], false), None);
+        assert_eq!(
+            parse_ordered_list(&["v. Smith was decided in 1803"], false),
+            None
+        );
+        assert_eq!(
+            parse_ordered_list(&["i. e. not a list", "at all"], false),
+            None
+        );
+        assert_eq!(parse_ordered_list(&["a) b", "", "c"], false), None);
+        assert_eq!(parse_ordered_list(&["vx. test"], false), None);
+        assert_eq!(parse_ordered_list(&["... test"], false), None);
     }
 
     #[test]
     fn no_early_matching() {
         assert_eq!(
-            parse_ordered_list(&vec!["test", "1. not", "2. a list"]),
+            parse_ordered_list(&["test", "1. not", "2. a list"], false),
             None
         );
     }
@@ -1,20 +1,28 @@
+use parser::attributes::split_trailing_attributes;
 use parser::span::parse_spans;
 use parser::Block;
 use parser::Block::Header;
 use regex::Regex;
 
-pub fn parse_setext_header(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_setext_header(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
     lazy_static! {
         static ref HORIZONTAL_RULE_1: Regex = Regex::new(r"^===+$").unwrap();
         static ref HORIZONTAL_RULE_2: Regex = Regex::new(r"^---+$").unwrap();
     }
 
     if lines.len() > 1 && !lines[0].is_empty() {
-        if HORIZONTAL_RULE_1.is_match(lines[1]) {
-            return Some((Header(parse_spans(lines[0]), 1), 2));
+        let level = if HORIZONTAL_RULE_1.is_match(lines[1]) {
+            1
         } else if HORIZONTAL_RULE_2.is_match(lines[1]) {
-            return Some((Header(parse_spans(lines[0]), 2), 2));
-        }
+            2
+        } else {
+            return None;
+        };
+        let (text, attributes) = split_trailing_attributes(lines[0]);
+        return Some((
+            Header(parse_spans(text, extended_autolinks), level, attributes),
+            2,
+        ));
     }
     None
 }
@@ -28,23 +36,23 @@ mod test {
     #[test]
     fn finds_atx_header() {
         assert_eq!(
-            parse_setext_header(&vec!["Test", "=========="]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 1), 2)
+            parse_setext_header(&["Test", "=========="], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 1, None), 2)
         );
 
         assert_eq!(
-            parse_setext_header(&vec!["Test", "----------"]).unwrap(),
-            (Header(vec![Text("Test".to_owned())], 2), 2)
+            parse_setext_header(&["Test", "----------"], false).unwrap(),
+            (Header(vec![Text("Test".to_owned())], 2, None), 2)
         );
 
         assert_eq!(
-            parse_setext_header(&vec!["This is a test", "==="]).unwrap(),
-            (Header(vec![Text("This is a test".to_owned())], 1), 2)
+            parse_setext_header(&["This is a test", "==="], false).unwrap(),
+            (Header(vec![Text("This is a test".to_owned())], 1, None), 2)
         );
 
         assert_eq!(
-            parse_setext_header(&vec!["This is a test", "---"]).unwrap(),
-            (Header(vec![Text("This is a test".to_owned())], 2), 2)
+            parse_setext_header(&["This is a test", "---"], false).unwrap(),
+            (Header(vec![Text("This is a test".to_owned())], 2, None), 2)
         );
     }
 }
@@ -0,0 +1,216 @@
+use parser::span::parse_spans;
+use parser::Block::Table;
+use parser::{Alignment, Block};
+use regex::Regex;
+
+pub fn parse_table(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
+    lazy_static! {
+        static ref DELIMITER_ROW: Regex =
+            Regex::new(r"^ *\|? *:?-+:? *(\| *:?-+:? *)*\|? *$").unwrap();
+    }
+
+    // a table needs at least a header row and a delimiter row
+    if lines.len() < 2 || !lines[0].contains('|') || !DELIMITER_ROW.is_match(lines[1]) {
+        return None;
+    }
+
+    let header = split_row(lines[0]);
+    let alignments: Vec<Alignment> = split_row(lines[1])
+        .iter()
+        .map(|cell| parse_alignment(cell))
+        .collect();
+
+    // the header row must match the delimiter row in the number of cells
+    if header.len() != alignments.len() {
+        return None;
+    }
+
+    let mut rows = vec![];
+    let mut i = 2;
+    while i < lines.len() && !lines[i].trim().is_empty() && lines[i].contains('|') {
+        let mut cells = split_row(lines[i]);
+        // excess cells are ignored, missing cells are filled with empty ones
+        cells.resize(alignments.len(), String::new());
+        rows.push(
+            cells
+                .iter()
+                .map(|c| parse_spans(c, extended_autolinks))
+                .collect(),
+        );
+        i += 1;
+    }
+
+    Some((
+        Table(
+            header
+                .iter()
+                .map(|c| parse_spans(c, extended_autolinks))
+                .collect(),
+            alignments,
+            rows,
+        ),
+        i,
+    ))
+}
+
+// splits a table row at every unescaped pipe, ignoring leading and trailing pipes
+fn split_row(line: &str) -> Vec<String> {
+    let mut line = line.trim();
+    if line.starts_with('|') {
+        line = &line[1..];
+    }
+    if line.ends_with('|') && !line.ends_with("\\|") {
+        line = &line[..line.len() - 1];
+    }
+
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                // escaped pipes are part of the cell content
+                Some('|') => cell.push('|'),
+                Some(x) => {
+                    cell.push('\\');
+                    cell.push(x);
+                }
+                None => cell.push('\\'),
+            },
+            '|' => {
+                cells.push(cell.trim().to_owned());
+                cell = String::new();
+            }
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_owned());
+    cells
+}
+
+fn parse_alignment(cell: &str) -> Alignment {
+    match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Alignment::Center,
+        (true, false) => Alignment::Left,
+        (false, true) => Alignment::Right,
+        (false, false) => Alignment::None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_table;
+    use parser::Alignment;
+    use parser::Block::Table;
+    use parser::Span::{Code, Text};
+
+    #[test]
+    fn finds_table() {
+        assert_eq!(
+            parse_table(&["| a | b |", "| --- | --- |", "| c | d |"], false).unwrap(),
+            (
+                Table(
+                    vec![vec![Text("a".to_owned())], vec![Text("b".to_owned())]],
+                    vec![Alignment::None, Alignment::None],
+                    vec![vec![vec![Text("c".to_owned())], vec![Text("d".to_owned())]]]
+                ),
+                3
+            )
+        );
+
+        assert_eq!(
+            parse_table(&["a | b", "--|--"], false).unwrap(),
+            (
+                Table(
+                    vec![vec![Text("a".to_owned())], vec![Text("b".to_owned())]],
+                    vec![Alignment::None, Alignment::None],
+                    vec![]
+                ),
+                2
+            )
+        );
+    }
+
+    #[test]
+    fn finds_alignments() {
+        match parse_table(&["| a | b | c | d |", "|:--|:-:|--:|---|"], false) {
+            Some((Table(_, alignments, _), 2)) => assert_eq!(
+                alignments,
+                vec![
+                    Alignment::Left,
+                    Alignment::Center,
+                    Alignment::Right,
+                    Alignment::None
+                ]
+            ),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn escaped_pipes() {
+        match parse_table(
+            &["| a | b |", "|---|---|", "| `x \\| y` | c \\| d |"],
+            false,
+        ) {
+            Some((Table(_, _, rows), 3)) => assert_eq!(
+                rows,
+                vec![vec![
+                    vec![Code("x | y".to_owned())],
+                    vec![Text("c | d".to_owned())]
+                ]]
+            ),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn normalizes_cell_count() {
+        match parse_table(&["| a | b |", "|---|---|", "| c |", "| d | e | f |"], false) {
+            Some((Table(_, _, rows), 4)) => assert_eq!(
+                rows,
+                vec![
+                    vec![vec![Text("c".to_owned())], vec![]],
+                    vec![vec![Text("d".to_owned())], vec![Text("e".to_owned())]]
+                ]
+            ),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn knows_when_to_stop() {
+        match parse_table(
+            &["| a | b |", "|---|---|", "| c | d |", "", "| e | f |"],
+            false,
+        ) {
+            Some((Table(_, _, _), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+
+        match parse_table(
+            &["| a | b |", "|---|---|", "| c | d |", "no pipes here"],
+            false,
+        ) {
+            Some((Table(_, _, _), 3)) => (),
+            x => panic!("Found {:?}", x),
+        }
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_table(&["a | b"], false), None);
+        assert_eq!(parse_table(&["Test", "----"], false), None);
+        assert_eq!(parse_table(&["a | b", "----"], false), None);
+        assert_eq!(parse_table(&["| a | b |", "|---|---|---|"], false), None);
+        assert_eq!(parse_table(&["| a | b |", "| c | d |"], false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(
+            parse_table(&["Test", "| a | b |", "|---|---|"], false),
+            None
+        );
+    }
+}
@@ -1,10 +1,10 @@
-use parser::block::parse_blocks;
+use parser::block::{parse_blocks, parse_task_marker};
 use parser::Block;
 use parser::Block::{Paragraph, UnorderedList};
 use parser::ListItem;
 use regex::Regex;
 
-pub fn parse_unordered_list(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_unordered_list(lines: &[&str], extended_autolinks: bool) -> Option<(Block, usize)> {
     lazy_static! {
         static ref LIST_BEGIN: Regex =
             Regex::new(r"^(?P<indent> *)(-|\+|\*) (?P<content>.*)").unwrap();
@@ -41,7 +41,8 @@ pub fn parse_unordered_list(lines: &[&str]) -> Option<(Block, usize)> {
 
         let caps = LIST_BEGIN.captures(line.unwrap()).unwrap();
 
-        let mut content = caps.name("content").unwrap().as_str().to_owned();
+        let (checked, content) = parse_task_marker(caps.name("content").unwrap().as_str());
+        let mut content = content.to_owned();
         let last_indent = caps.name("indent").unwrap().as_str().len();
         i += 1;
 
@@ -62,28 +63,26 @@ pub fn parse_unordered_list(lines: &[&str]) -> Option<(Block, usize)> {
             }
 
             // newline means we start a new paragraph
-            if line.unwrap().is_empty() {
-                prev_newline = true;
-            } else {
-                prev_newline = false;
-            }
+            prev_newline = line.unwrap().is_empty();
 
             content.push('\n');
             let caps = INDENTED.captures(line.unwrap()).unwrap();
-            content.push_str(&caps.name("content").unwrap().as_str());
+            content.push_str(caps.name("content").unwrap().as_str());
 
             i += 1;
         }
-        contents.push(parse_blocks(&content));
+        contents.push((parse_blocks(&content, extended_autolinks), checked));
     }
 
     let mut list_contents = vec![];
 
-    for c in contents {
+    for (c, checked) in contents {
         if is_paragraph || c.len() > 1 {
-            list_contents.push(ListItem::Paragraph(c));
+            list_contents.push(ListItem::Paragraph(c, checked));
+        } else if c.is_empty() {
+            list_contents.push(ListItem::Simple(vec![], checked));
         } else if let Paragraph(content) = c[0].clone() {
-            list_contents.push(ListItem::Simple(content));
+            list_contents.push(ListItem::Simple(content, checked));
         }
     }
 
@@ -98,28 +97,73 @@ pub fn parse_unordered_list(lines: &[&str]) -> Option<(Block, usize)> {
 mod test {
     use super::parse_unordered_list;
     use parser::Block::UnorderedList;
+    use parser::ListItem::Simple;
+    use parser::Span::{RefLink, Text};
 
     #[test]
     fn finds_list() {
-        match parse_unordered_list(&vec!["* A list", "* is good"]) {
+        match parse_unordered_list(&["* A list", "* is good"], false) {
             Some((UnorderedList(_), 2)) => (),
             x => panic!("Found {:?}", x),
         }
 
-        match parse_unordered_list(&vec!["* A list", "* is good", "laksjdnflakdsjnf"]) {
+        match parse_unordered_list(&["* A list", "* is good", "laksjdnflakdsjnf"], false) {
             Some((UnorderedList(_), 3)) => (),
             x => panic!("Found {:?}", x),
         }
     }
 
+    #[test]
+    fn finds_task_list() {
+        assert_eq!(
+            parse_unordered_list(
+                &["- [ ] todo", "- [x] done", "- [X] also done", "- no task"],
+                false
+            ),
+            Some((
+                UnorderedList(vec![
+                    Simple(vec![Text("todo".to_owned())], Some(false)),
+                    Simple(vec![Text("done".to_owned())], Some(true)),
+                    Simple(vec![Text("also done".to_owned())], Some(true)),
+                    Simple(vec![Text("no task".to_owned())], None)
+                ]),
+                4
+            ))
+        );
+
+        assert_eq!(
+            parse_unordered_list(&["- [ ]", "- [y] no task", "- [x]no task"], false),
+            Some((
+                UnorderedList(vec![
+                    Simple(vec![], Some(false)),
+                    Simple(
+                        vec![
+                            RefLink(vec![Text("y".to_owned())], "".to_owned(), "[y]".to_owned()),
+                            Text(" no task".to_owned())
+                        ],
+                        None
+                    ),
+                    Simple(
+                        vec![
+                            RefLink(vec![Text("x".to_owned())], "".to_owned(), "[x]".to_owned()),
+                            Text("no task".to_owned())
+                        ],
+                        None
+                    )
+                ]),
+                3
+            ))
+        );
+    }
+
     #[test]
     fn knows_when_to_stop() {
-        match parse_unordered_list(&vec!["* A list", "* is good", "", "laksjdnflakdsjnf"]) {
+        match parse_unordered_list(&["* A list", "* is good", "", "laksjdnflakdsjnf"], false) {
             Some((UnorderedList(_), 3)) => (),
             x => panic!("Found {:?}", x),
         }
 
-        match parse_unordered_list(&vec!["* A list", "", "laksjdnflakdsjnf"]) {
+        match parse_unordered_list(&["* A list", "", "laksjdnflakdsjnf"], false) {
             Some((UnorderedList(_), 2)) => (),
             x => panic!("Found {:?}", x),
         }
@@ -127,13 +171,13 @@ mod test {
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_unordered_list(&vec!["test * test"]), None);
+        assert_eq!(parse_unordered_list(&["test * test"], false), None);
     }
 
     #[test]
     fn no_early_matching() {
         assert_eq!(
-            parse_unordered_list(&vec!["test", "* whot", "* a list"]),
+            parse_unordered_list(&["test", "* whot", "* a list"], false),
             None
         );
     }
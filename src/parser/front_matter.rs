@@ -0,0 +1,203 @@
+use parser::Block;
+use parser::Block::FrontMatter as FrontMatterBlock;
+
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FrontMatterFormat {
+    /** Delimited by `---` lines **/
+    Yaml,
+    /** Delimited by `+++` lines **/
+    Toml,
+}
+
+impl FrontMatterFormat {
+    /// The line that opens and closes front matter of this format
+    pub fn delimiter(self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "---",
+            FrontMatterFormat::Toml => "+++",
+        }
+    }
+}
+
+/// The metadata block at the start of a document
+#[derive(Debug, PartialEq, Clone)]
+pub struct FrontMatter {
+    /// The format of the metadata, as determined by its delimiters
+    pub format: FrontMatterFormat,
+    /// The metadata text between the delimiters, exactly as written
+    pub raw: String,
+    /// The top-level `key: value` (YAML) or `key = value` (TOML) pairs in
+    /// the order they appear in. Quotes around keys and values are removed,
+    /// nested values are left out.
+    pub fields: Vec<(String, String)>,
+}
+
+impl FrontMatter {
+    /// Returns the value of the first field named `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|&(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// splits a front matter block from the start of the document
+// and returns it along with the rest of the document
+pub fn split_front_matter(md: &str) -> (Option<Block>, &str) {
+    let mut segments = md.split_inclusive('\n');
+    let format = match segments.next().map(|l| l.trim_end()) {
+        Some("---") => FrontMatterFormat::Yaml,
+        Some("+++") => FrontMatterFormat::Toml,
+        _ => return (None, md),
+    };
+
+    let start = md.find('\n').unwrap_or(md.len()) + 1;
+    let mut end = start;
+    for segment in segments {
+        if segment.trim_end() == format.delimiter() {
+            let raw = md[start..end].trim_end_matches(&['\r', '\n'][..]);
+            return (
+                Some(FrontMatterBlock(format, raw.to_owned())),
+                &md[end + segment.len()..],
+            );
+        }
+        end += segment.len();
+    }
+
+    // without a closing delimiter this is not front matter
+    (None, md)
+}
+
+pub fn to_front_matter(format: FrontMatterFormat, raw: &str) -> FrontMatter {
+    let separator = match format {
+        FrontMatterFormat::Yaml => ':',
+        FrontMatterFormat::Toml => '=',
+    };
+
+    let mut fields = vec![];
+    for line in raw.lines() {
+        // fields of TOML tables are not top-level fields
+        if format == FrontMatterFormat::Toml && line.starts_with('[') {
+            break;
+        }
+        if line.starts_with(char::is_whitespace) || line.starts_with('#') || line.starts_with('-') {
+            continue;
+        }
+        if let Some(pos) = line.find(separator) {
+            let (key, value) = (unquote(&line[..pos]), unquote(&line[pos + 1..]));
+            if !key.is_empty() && !value.is_empty() {
+                fields.push((key.to_owned(), value.to_owned()));
+            }
+        }
+    }
+
+    FrontMatter {
+        format,
+        raw: raw.to_owned(),
+        fields,
+    }
+}
+
+fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    if text.len() >= 2
+        && ((text.starts_with('"') && text.ends_with('"'))
+            || (text.starts_with('\'') && text.ends_with('\'')))
+    {
+        return &text[1..text.len() - 1];
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::{split_front_matter, to_front_matter, FrontMatterFormat};
+    use parser::Block::FrontMatter;
+
+    #[test]
+    fn finds_front_matter() {
+        assert_eq!(
+            split_front_matter("---\ntitle: Test\n---\n# Test"),
+            (
+                Some(FrontMatter(
+                    FrontMatterFormat::Yaml,
+                    "title: Test".to_owned()
+                )),
+                "# Test"
+            )
+        );
+
+        assert_eq!(
+            split_front_matter("+++\r\ntitle = \"Test\"\r\n+++\r\n\r\nTest"),
+            (
+                Some(FrontMatter(
+                    FrontMatterFormat::Toml,
+                    "title = \"Test\"".to_owned()
+                )),
+                "\r\nTest"
+            )
+        );
+
+        assert_eq!(
+            split_front_matter("---\n---"),
+            (
+                Some(FrontMatter(FrontMatterFormat::Yaml, "".to_owned())),
+                ""
+            )
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(
+            split_front_matter("---\ntitle: Test"),
+            (None, "---\ntitle: Test")
+        );
+        assert_eq!(
+            split_front_matter("---\ntitle: Test\n+++"),
+            (None, "---\ntitle: Test\n+++")
+        );
+        assert_eq!(split_front_matter("----\na\n----"), (None, "----\na\n----"));
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(split_front_matter("\n---\na\n---"), (None, "\n---\na\n---"));
+        assert_eq!(
+            split_front_matter("Test\n---\na\n---"),
+            (None, "Test\n---\na\n---")
+        );
+    }
+
+    #[test]
+    fn parses_fields() {
+        let front_matter = to_front_matter(
+            FrontMatterFormat::Yaml,
+            "title: \"A: Test\"\n# comment\ntags:\n  - a\n  - b\nauthor: 'Me'\ndate: 2020-01-01",
+        );
+        assert_eq!(
+            front_matter.fields,
+            vec![
+                ("title".to_owned(), "A: Test".to_owned()),
+                ("author".to_owned(), "Me".to_owned()),
+                ("date".to_owned(), "2020-01-01".to_owned())
+            ]
+        );
+        assert_eq!(front_matter.get("author"), Some("Me"));
+        assert_eq!(front_matter.get("tags"), None);
+
+        let front_matter = to_front_matter(
+            FrontMatterFormat::Toml,
+            "title = \"Test\"\ndraft = false\n\n[extra]\nkey = \"value\"",
+        );
+        assert_eq!(
+            front_matter.fields,
+            vec![
+                ("title".to_owned(), "Test".to_owned()),
+                ("draft".to_owned(), "false".to_owned())
+            ]
+        );
+    }
+}
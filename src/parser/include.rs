@@ -0,0 +1,457 @@
+use html::header_id;
+use options::{FileSystemLoader, IncludeLoader};
+use parser::transform::flat_map_blocks;
+use parser::Block::{CodeBlock, FrontMatter, Header, Paragraph};
+use parser::Span::Text;
+use parser::{parse_markdown, Block, Span};
+use regex::Regex;
+use std::cell::RefCell;
+use std::path::{Component, Path, PathBuf};
+use Options;
+
+const MAX_DEPTH: usize = 8;
+
+// The part of an included file that is used
+enum Selection<'a> {
+    All,
+    // the first and last line, starting at 1, like in `path:3:10`
+    Lines(usize, Option<usize>),
+    // a section like `path#install`
+    Anchor(&'a str),
+}
+
+struct Includes<'a> {
+    loader: &'a dyn IncludeLoader,
+    // included files must be inside of this directory
+    root: PathBuf,
+    max_depth: usize,
+    extended_autolinks: bool,
+    // the files that are being included, to detect cycles
+    stack: RefCell<Vec<PathBuf>>,
+}
+
+// Replaces paragraphs like `{{#include path.md}}` with the content of the
+// file, relative to the file at `path`. Without a path, they are relative to
+// the include root, and without either they are left alone.
+pub fn resolve_includes(blocks: Vec<Block>, path: Option<&Path>, options: &Options) -> Vec<Block> {
+    let dir = match (path, options.include_root.as_deref()) {
+        (Some(path), _) => path.parent().unwrap_or(path),
+        (None, Some(root)) => root,
+        (None, None) => return blocks,
+    };
+    let includes = Includes {
+        loader: match options.include_loader {
+            Some(ref loader) => &**loader,
+            None => &FileSystemLoader,
+        },
+        root: normalize(options.include_root.as_deref().unwrap_or(dir)),
+        max_depth: options.max_include_depth.unwrap_or(MAX_DEPTH),
+        extended_autolinks: options.extended_autolinks,
+        stack: RefCell::new(path.map(normalize).into_iter().collect()),
+    };
+    includes.resolve(blocks, dir)
+}
+
+impl<'a> Includes<'a> {
+    // includes the files of the paragraphs, relative to the directory `dir`
+    fn resolve(&self, blocks: Vec<Block>, dir: &Path) -> Vec<Block> {
+        flat_map_blocks(blocks, &|block| {
+            let included = match block {
+                Paragraph(ref spans) => self.include(spans, dir),
+                _ => None,
+            };
+            included.unwrap_or_else(|| vec![block])
+        })
+    }
+
+    // Returns the blocks of the file included by the paragraph, or nothing if
+    // it isn't an include or the file can't be included.
+    fn include(&self, spans: &[Span], dir: &Path) -> Option<Vec<Block>> {
+        lazy_static! {
+            static ref INCLUDE: Regex = Regex::new(
+                r"^\{\{#include\s+(?P<path>[^\s:#}]+)(?:#(?P<anchor>[^\s}]+)|:(?P<start>[0-9]*)(?::(?P<end>[0-9]*))?)?\s*\}\}$"
+            )
+            .unwrap();
+        }
+
+        // an include is parsed into a single span, like `{{toc}}`
+        let caps = match *spans {
+            [Text(ref text)] => INCLUDE.captures(text)?,
+            _ => return None,
+        };
+        // absolute paths and paths leading out of the root could be used to
+        // read any file on the system
+        if Path::new(&caps["path"]).has_root() {
+            return None;
+        }
+        let file = normalize(&dir.join(&caps["path"]));
+        if !self.contains(&file) {
+            return None;
+        }
+
+        let selection = if let Some(anchor) = caps.name("anchor") {
+            Selection::Anchor(anchor.as_str())
+        } else if let Some(start) = caps.name("start") {
+            let start = start.as_str().parse().ok();
+            // `path:3` includes only the third line, `path:3:` the rest
+            let end = match caps.name("end") {
+                Some(end) => end.as_str().parse().ok(),
+                None => start,
+            };
+            Selection::Lines(start.unwrap_or(1), end)
+        } else {
+            Selection::All
+        };
+
+        if self.stack.borrow().contains(&file) || self.stack.borrow().len() > self.max_depth {
+            return None;
+        }
+        let content = self.loader.load(&file).ok()?;
+
+        if !is_markdown(&file) {
+            let code = match selection {
+                Selection::All => content,
+                Selection::Lines(start, end) => select_lines(&content, start, end),
+                Selection::Anchor(anchor) => select_anchor(&content, anchor)?,
+            };
+            let code = code.trim_end_matches('\n').to_owned();
+            return Some(vec![CodeBlock(language(&file), code, None)]);
+        }
+
+        let content = match selection {
+            Selection::Lines(start, end) => select_lines(&content, start, end),
+            _ => content,
+        };
+        let mut blocks = parse_markdown(&content, self.extended_autolinks)
+            .into_iter()
+            .filter(|block| !matches!(*block, FrontMatter(_, _)))
+            .collect();
+        if let Selection::Anchor(anchor) = selection {
+            blocks = select_section(blocks, anchor)?;
+        }
+
+        self.stack.borrow_mut().push(file.clone());
+        let blocks = self.resolve(blocks, file.parent().unwrap_or(&file));
+        self.stack.borrow_mut().pop();
+        Some(blocks)
+    }
+
+    // whether the normalized path is inside of the root
+    fn contains(&self, file: &Path) -> bool {
+        match file.strip_prefix(&self.root) {
+            Ok(rest) => rest
+                .components()
+                .all(|component| matches!(component, Component::Normal(_))),
+            Err(_) => false,
+        }
+    }
+}
+
+fn is_markdown(path: &Path) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => {
+            ["md", "markdown", "mdown", "mkd"].contains(&extension.to_lowercase().as_str())
+        }
+        None => false,
+    }
+}
+
+// the language of a code file, mostly named like its extension
+fn language(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let language = match extension.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "js" => "javascript",
+        "ts" => "typescript",
+        "rb" => "ruby",
+        "sh" => "bash",
+        "yml" => "yaml",
+        "h" => "c",
+        "cc" | "cxx" | "hpp" => "cpp",
+        "kt" => "kotlin",
+        "cs" => "csharp",
+        "txt" => "text",
+        extension => extension,
+    };
+    Some(language.to_owned())
+}
+
+fn select_lines(content: &str, start: usize, end: Option<usize>) -> String {
+    let start = start.max(1);
+    let count = end.map_or(usize::MAX, |end| (end + 1).saturating_sub(start));
+    content
+        .lines()
+        .skip(start - 1)
+        .take(count)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Returns the lines between `ANCHOR: name` and `ANCHOR_END: name` like
+// mdBook does. The lines of other anchors are left out.
+fn select_anchor(content: &str, anchor: &str) -> Option<String> {
+    lazy_static! {
+        static ref MARKER: Regex = Regex::new(r"ANCHOR(?P<end>_END)?:\s*(?P<name>[\w-]+)").unwrap();
+    }
+
+    let mut lines = vec![];
+    let mut found = false;
+    let mut inside = false;
+    for line in content.lines() {
+        match MARKER.captures(line) {
+            Some(ref caps) if &caps["name"] == anchor => {
+                inside = caps.name("end").is_none();
+                found = true;
+            }
+            Some(_) => {}
+            None if inside => lines.push(line),
+            None => {}
+        }
+    }
+    if found {
+        Some(lines.join("\n"))
+    } else {
+        None
+    }
+}
+
+// Returns the header with the given id and the blocks up to the next header
+// of the same or a higher level.
+fn select_section(mut blocks: Vec<Block>, anchor: &str) -> Option<Vec<Block>> {
+    let start = blocks.iter().position(|block| match *block {
+        Header(ref elements, _, ref attributes) => header_id(elements, attributes) == anchor,
+        _ => false,
+    })?;
+    let level = match blocks[start] {
+        Header(_, level, _) => level,
+        _ => unreachable!(),
+    };
+    let end = blocks[start + 1..]
+        .iter()
+        .position(|block| matches!(*block, Header(_, l, _) if l <= level))
+        .map_or(blocks.len(), |i| start + 1 + i);
+    Some(blocks.drain(start..end).collect())
+}
+
+// removes `.` and `..` from a path without looking at the file system, so
+// that the same file is always found under the same path
+fn normalize(path: &Path) -> PathBuf {
+    let mut ret = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(ret.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                ret.pop();
+            }
+            component => ret.push(component.as_os_str()),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::resolve_includes;
+    use options::IncludeLoader;
+    use parser::parse;
+    use parser::Block::{CodeBlock, Header, Paragraph};
+    use parser::Span::Text;
+    use std::collections::HashMap;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use Options;
+
+    struct MemoryLoader(HashMap<&'static str, &'static str>);
+
+    impl IncludeLoader for MemoryLoader {
+        fn load(&self, path: &Path) -> io::Result<String> {
+            match path.to_str().and_then(|path| self.0.get(path)) {
+                Some(content) => Ok((*content).to_owned()),
+                None => Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+            }
+        }
+    }
+
+    fn include(md: &str, files: &[(&'static str, &'static str)]) -> Vec<::parser::Block> {
+        include_from(md, files, None)
+    }
+
+    fn include_from(
+        md: &str,
+        files: &[(&'static str, &'static str)],
+        root: Option<&str>,
+    ) -> Vec<::parser::Block> {
+        let options = Options {
+            includes: true,
+            include_loader: Some(Arc::new(MemoryLoader(files.iter().cloned().collect()))),
+            include_root: root.map(PathBuf::from),
+            max_include_depth: Some(3),
+            ..Default::default()
+        };
+        resolve_includes(parse(md), Some(Path::new("docs/index.md")), &options)
+    }
+
+    fn text(text: &str) -> ::parser::Block {
+        Paragraph(vec![Text(text.to_owned())])
+    }
+
+    #[test]
+    fn includes_files() {
+        assert_eq!(
+            include(
+                "{{#include shared/install.md}}",
+                &[
+                    (
+                        "docs/shared/install.md",
+                        "Install it:\n\n{{#include ../main.rs}}"
+                    ),
+                    ("docs/main.rs", "fn main() {}\n"),
+                ]
+            ),
+            vec![
+                text("Install it:"),
+                CodeBlock(Some("rust".to_owned()), "fn main() {}".to_owned(), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn includes_parts() {
+        let files = [
+            ("docs/a.md", "one\n\ntwo\n\nthree"),
+            (
+                "docs/b.md",
+                "# Intro\n\nhi\n\n## Install {#setup}\n\nrun it\n\n### Details\n\nmore\n\n## Usage\n\nuse it",
+            ),
+            ("docs/c.py", "# ANCHOR: run\nrun()\n# ANCHOR: inner\ninner()\n# ANCHOR_END: inner\n# ANCHOR_END: run\nexit()"),
+        ];
+        assert_eq!(include("{{#include a.md:3:3}}", &files), vec![text("two")]);
+        assert_eq!(
+            include("{{#include a.md:3:}}", &files),
+            vec![text("two"), text("three")]
+        );
+        assert_eq!(
+            include("{{#include b.md#setup}}", &files),
+            parse("## Install {#setup}\n\nrun it\n\n### Details\n\nmore")
+        );
+        assert_eq!(
+            include("{{#include c.py#run}}", &files),
+            vec![CodeBlock(
+                Some("python".to_owned()),
+                "run()\ninner()".to_owned(),
+                None
+            )]
+        );
+        assert_eq!(
+            include("{{#include c.py::1}}", &files),
+            vec![CodeBlock(
+                Some("python".to_owned()),
+                "# ANCHOR: run".to_owned(),
+                None
+            )]
+        );
+        assert_eq!(
+            include("{{#include b.md#usage}}", &files),
+            vec![
+                Header(vec![Text("Usage".to_owned())], 2, None),
+                text("use it")
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_cycles_and_depth() {
+        assert_eq!(
+            include(
+                "{{#include a.md}}",
+                &[
+                    ("docs/a.md", "a\n\n{{#include b.md}}"),
+                    ("docs/b.md", "b\n\n{{#include ./a.md}}")
+                ]
+            ),
+            vec![text("a"), text("b"), text("{{#include ./a.md}}")]
+        );
+        assert_eq!(
+            include(
+                "{{#include 1.md}}",
+                &[
+                    ("docs/1.md", "{{#include 2.md}}"),
+                    ("docs/2.md", "{{#include 3.md}}"),
+                    ("docs/3.md", "{{#include 4.md}}"),
+                    ("docs/4.md", "too deep")
+                ]
+            ),
+            vec![text("{{#include 4.md}}")]
+        );
+    }
+
+    #[test]
+    fn stays_inside_of_the_root() {
+        let files = [
+            ("/etc/hostname", "host"),
+            ("secret.md", "secret"),
+            ("docs/guide/page.md", "{{#include ../../secret.md}}"),
+        ];
+        assert_eq!(
+            include(
+                "{{#include /etc/hostname}}\n\n{{#include ../secret.md}}\n\n{{#include guide/page.md}}",
+                &files
+            ),
+            vec![
+                text("{{#include /etc/hostname}}"),
+                text("{{#include ../secret.md}}"),
+                text("{{#include ../../secret.md}}")
+            ]
+        );
+        assert_eq!(
+            include_from(
+                "{{#include ../secret.md}}\n\n{{#include /etc/hostname}}",
+                &files,
+                Some(".")
+            ),
+            vec![text("secret"), text("{{#include /etc/hostname}}")]
+        );
+        assert_eq!(
+            include_from("{{#include page.md}}", &files, Some("docs/guide")),
+            vec![text("{{#include page.md}}")]
+        );
+    }
+
+    #[test]
+    fn needs_a_document_or_root() {
+        let files = [("a.md", "cwd"), ("docs/a.md", "docs")];
+        let options = |root: Option<&str>| Options {
+            includes: true,
+            include_loader: Some(Arc::new(MemoryLoader(files.iter().cloned().collect()))),
+            include_root: root.map(PathBuf::from),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_includes(parse("{{#include a.md}}"), None, &options(None)),
+            vec![text("{{#include a.md}}")]
+        );
+        assert_eq!(
+            resolve_includes(parse("{{#include a.md}}"), None, &options(Some("docs"))),
+            vec![text("docs")]
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(
+            include(
+                "{{#include missing.md}}\n\nsee {{#include a.md}}\n\n{{#include b.md#missing}}",
+                &[("docs/a.md", "a"), ("docs/b.md", "b")]
+            ),
+            vec![
+                text("{{#include missing.md}}"),
+                text("see {{#include a.md}}"),
+                text("{{#include b.md#missing}}")
+            ]
+        );
+    }
+}
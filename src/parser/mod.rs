@@ -1,5 +1,14 @@
+use std::path::Path;
+use Options;
+
+mod attributes;
 mod block;
+mod front_matter;
+mod include;
+mod smart_punctuation;
 mod span;
+mod transform;
+pub use self::front_matter::{FrontMatter, FrontMatterFormat};
 
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -12,16 +21,7 @@ pub enum OrderedListType {
 }
 
 impl OrderedListType {
-    pub fn from_str(type_str: &str) -> OrderedListType {
-        match type_str {
-            "a" => OrderedListType::Lowercase,
-            "A" => OrderedListType::Uppercase,
-            "i" => OrderedListType::LowercaseRoman,
-            "I" => OrderedListType::UppercaseRoman,
-            _ => OrderedListType::Numeric,
-        }
-    }
-
+    /// Returns the `type` attribute of an HTML list with this numbering, e.g. `a`
     pub fn to_str(&self) -> &'static str {
         match self {
             OrderedListType::Lowercase => "a",
@@ -31,19 +31,188 @@ impl OrderedListType {
             OrderedListType::Numeric => "1",
         }
     }
+
+    /// Formats a number in this numbering, e.g. 4 as `d` or `iv`
+    pub fn format_number(&self, n: usize) -> String {
+        match self {
+            OrderedListType::Numeric => n.to_string(),
+            OrderedListType::Lowercase => to_letters(n),
+            OrderedListType::Uppercase => to_letters(n).to_uppercase(),
+            OrderedListType::LowercaseRoman => to_roman(n),
+            OrderedListType::UppercaseRoman => to_roman(n).to_uppercase(),
+        }
+    }
+}
+
+// numbers letters like HTML does: a, b, ..., z, aa, ab, ...
+fn to_letters(mut n: usize) -> String {
+    let mut letters = vec![];
+    while n > 0 {
+        n -= 1;
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.into_iter().rev().collect()
+}
+
+pub fn to_roman(mut n: usize) -> String {
+    const NUMERALS: &[(usize, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut roman = String::new();
+    for &(value, numeral) in NUMERALS {
+        while n >= value {
+            roman.push_str(numeral);
+            n -= value;
+        }
+    }
+    roman
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OrderedListDelimiter {
+    /** A period like in `1.` **/
+    Period,
+    /** A closing parenthesis like in `1)` **/
+    Parenthesis,
+}
+
+impl OrderedListDelimiter {
+    /// Returns the delimiter as it is written after the number
+    pub fn to_char(self) -> char {
+        match self {
+            OrderedListDelimiter::Period => '.',
+            OrderedListDelimiter::Parenthesis => ')',
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AdmonitionKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AdmonitionKind {
+    /// Returns the kind for a marker like `NOTE`, ignoring case
+    pub fn from_marker(s: &str) -> Option<AdmonitionKind> {
+        match s.to_uppercase().as_str() {
+            "NOTE" => Some(AdmonitionKind::Note),
+            "TIP" => Some(AdmonitionKind::Tip),
+            "IMPORTANT" => Some(AdmonitionKind::Important),
+            "WARNING" => Some(AdmonitionKind::Warning),
+            "CAUTION" => Some(AdmonitionKind::Caution),
+            _ => None,
+        }
+    }
+
+    /// Returns the marker of this kind, e.g. `NOTE`
+    pub fn to_marker(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "NOTE",
+            AdmonitionKind::Tip => "TIP",
+            AdmonitionKind::Important => "IMPORTANT",
+            AdmonitionKind::Warning => "WARNING",
+            AdmonitionKind::Caution => "CAUTION",
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReferenceKind {
+    /** An issue or pull request like `#123` or `owner/repo#123` **/
+    Issue,
+    /** A commit like `a5c3785` or `owner/repo@a5c3785` **/
+    Commit,
+    /** A user like `@octocat` **/
+    Mention,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// An attribute list like `{#id .class key=value}`
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Attributes {
+    /// The `#id`, the last one wins if there are several
+    pub id: Option<String>,
+    /// The `.class` names in order
+    pub classes: Vec<String>,
+    /// The `key=value` pairs in order
+    pub pairs: Vec<(String, String)>,
+}
+
+impl Attributes {
+    /// Returns the value of the last `key=value` pair with the given key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|&(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
 }
 
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum Block {
-    Header(Vec<Span>, usize),
+    /** A metadata block at the start of the document with the fields: (format, raw text) **/
+    FrontMatter(FrontMatterFormat, String),
+    /** A header with the fields: (content, level, [attributes]) **/
+    Header(Vec<Span>, usize, Option<Attributes>),
     Paragraph(Vec<Span>),
     Blockquote(Vec<Block>),
-    CodeBlock(Option<String>, String),
+    /** A blockquote starting with a marker like `[!NOTE]`, with the fields: (kind, content) **/
+    Admonition(AdmonitionKind, Vec<Block>),
+    /** A code block with the fields: ([language], code, [attributes]) **/
+    CodeBlock(Option<String>, String, Option<Attributes>),
+    /** A display formula with its TeX source **/
+    DisplayMath(String),
     /** A link reference with the fields: (id, url, [title]) **/
     LinkReference(String, String, Option<String>),
-    OrderedList(Vec<ListItem>, OrderedListType),
+    /** An abbreviation definition with the fields: (abbreviation, title) **/
+    AbbreviationDefinition(String, String),
+    /** A footnote definition with the fields: (id, content) **/
+    FootnoteDefinition(String, Vec<Block>),
+    /** An ordered list with the fields: (items, numbering, start number, delimiter) **/
+    OrderedList(Vec<ListItem>, OrderedListType, usize, OrderedListDelimiter),
     UnorderedList(Vec<ListItem>),
+    /** A definition list made of (term, definitions) entries **/
+    DefinitionList(Vec<(Vec<Span>, Vec<Vec<Block>>)>),
+    /** A table with the fields: (header cells, column alignments, body rows) **/
+    Table(Vec<Vec<Span>>, Vec<Alignment>, Vec<Vec<Vec<Span>>>),
+    /** A directive like `::youtube[label]{id=abc}` with the fields: (name, label, [attributes]) **/
+    LeafDirective(String, Vec<Span>, Option<Attributes>),
+    /**
+     * A directive like `:::note[label]{.tip}` ... `:::` with the fields:
+     * (name, label, [attributes], content)
+     **/
+    ContainerDirective(String, Vec<Span>, Option<Attributes>, Vec<Block>),
     Raw(String),
     Hr,
 }
@@ -51,30 +220,128 @@ pub enum Block {
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum ListItem {
-    Simple(Vec<Span>),
-    Paragraph(Vec<Block>),
+    /** A list item with the fields: (content, [checked]). The second field is only set for task list items **/
+    Simple(Vec<Span>, Option<bool>),
+    /** A list item with the fields: (content, [checked]). The second field is only set for task list items **/
+    Paragraph(Vec<Block>, Option<bool>),
 }
 
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum Span {
+    /** A hard line break **/
     Break,
+    /** A newline inside of a paragraph that isn't a hard line break **/
+    SoftBreak,
     Text(String),
     Code(String),
+    /** An inline formula with its TeX source **/
+    InlineMath(String),
+    /** A display formula inside of a paragraph, like `$$x$$`, with its TeX source **/
+    InlineDisplayMath(String),
     Literal(char),
-    Link(Vec<Span>, String, Option<String>),
+    /** A link with the fields: (content, url, [title], [attributes]) **/
+    Link(Vec<Span>, String, Option<String>, Option<Attributes>),
     /**
      * A reference-style link with the fields: (content, url, raw)
      * The "raw" field is used internally for falling back to the original
      * markdown link if the corresponding reference is not found at render time.
      **/
     RefLink(Vec<Span>, String, String),
-    Image(String, String, Option<String>),
+    /** An image with the fields: (alt text, url, [title], [attributes]) **/
+    Image(String, String, Option<String>, Option<Attributes>),
+    /**
+     * A reference-style image with the fields: (alt text, reference, raw)
+     * Like for `RefLink`, the "raw" field is the original markdown that is
+     * rendered if the reference is not found.
+     **/
+    RefImage(String, String, String),
+    /** A wiki-style link with the fields: (page name, [label]) **/
+    WikiLink(String, Option<String>),
+    /** A reference to the footnote with the given id **/
+    FootnoteReference(String),
+    /**
+     * An automatic link with the fields: (text, url, raw)
+     * The "raw" field holds the original markdown, which is the text either
+     * in angle brackets or, for extended autolinks, as it was found in the text.
+     **/
+    AutoLink(String, String, String),
+    /** A reference to an issue, commit or user with the fields: (kind, text, url) **/
+    Reference(ReferenceKind, String, String),
+    /** Inline HTML that is passed through unchanged **/
+    Html(String),
+    /** A directive like `:kbd[label]{.key}` with the fields: (name, label, [attributes]) **/
+    InlineDirective(String, Vec<Span>, Option<Attributes>),
 
     Emphasis(Vec<Span>),
     Strong(Vec<Span>),
+    Strikethrough(Vec<Span>),
+    Superscript(Vec<Span>),
+    Subscript(Vec<Span>),
+    /** Highlighted text **/
+    Mark(Vec<Span>),
 }
 
 pub fn parse(md: &str) -> Vec<Block> {
-    block::parse_blocks(md)
+    parse_markdown(md, false)
+}
+
+// like `parse`, with bare urls parsed as autolinks if `extended_autolinks` is set
+pub fn parse_markdown(md: &str, extended_autolinks: bool) -> Vec<Block> {
+    match front_matter::split_front_matter(md) {
+        (Some(front_matter), rest) => {
+            let mut blocks = vec![front_matter];
+            blocks.extend(block::parse_blocks(rest, extended_autolinks));
+            blocks
+        }
+        (None, md) => block::parse_blocks(md, extended_autolinks),
+    }
+}
+
+pub fn parse_front_matter(md: &str) -> Option<FrontMatter> {
+    match front_matter::split_front_matter(md) {
+        (Some(Block::FrontMatter(format, raw)), _) => {
+            Some(front_matter::to_front_matter(format, &raw))
+        }
+        _ => None,
+    }
+}
+
+pub fn parse_with_options(md: &str, options: &Options) -> Vec<Block> {
+    parse_file_with_options(md, None, options)
+}
+
+// like `parse_with_options`, with includes relative to the file at `path`
+pub fn parse_file_with_options(md: &str, path: Option<&Path>, options: &Options) -> Vec<Block> {
+    let mut blocks = parse_markdown(md, options.extended_autolinks);
+    if options.includes {
+        blocks = include::resolve_includes(blocks, path, options);
+    }
+    if let Some(ref references) = options.references {
+        blocks = transform::map_text(blocks, false, &|text| {
+            span::find_references(text, references)
+        });
+    }
+    if options.emoji_shortcodes {
+        blocks = transform::map_text(blocks, true, &|text| {
+            span::expand_emoji(text, &options.custom_emoji)
+        });
+    }
+    if options.hard_breaks {
+        blocks = transform::map_inlines(blocks, &|spans| {
+            spans
+                .into_iter()
+                .map(|span| match span {
+                    Span::SoftBreak => Span::Break,
+                    span => span,
+                })
+                .collect()
+        });
+    }
+    if options.smart_punctuation {
+        blocks = transform::map_inlines(blocks, &|spans| {
+            smart_punctuation::smarten(spans, &options.quote_style)
+        });
+    }
+    blocks
 }
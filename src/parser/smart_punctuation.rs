@@ -0,0 +1,265 @@
+use options::QuoteStyle;
+use parser::Span;
+use parser::Span::{
+    Break, Emphasis, Html, InlineDirective, Link, Literal, Mark, RefLink, SoftBreak, Strikethrough,
+    Strong, Subscript, Superscript, Text,
+};
+
+const APOSTROPHE: &str = "\u{2019}";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Quote {
+    DoubleOpen,
+    DoubleClose,
+    SingleOpen,
+    SingleClose,
+    Apostrophe,
+}
+
+// A piece of the content of a block, either text that may be changed or
+// something else that is only looked at to decide which quotes to use
+enum Piece<'a> {
+    Text(&'a mut String),
+    Other(char),
+}
+
+// Replaces straight quotes, dashes and ellipses in the text of the spans
+// with their typographic counterparts. Quotes are paired across spans, so
+// that e.g. `"*emphasis*"` gets an opening and a closing quote.
+pub fn smarten(mut spans: Vec<Span>, quote_style: &QuoteStyle) -> Vec<Span> {
+    let mut pieces = vec![];
+    collect_pieces(&mut spans, &mut pieces);
+
+    // every char of the content together with the index of the text piece
+    // it belongs to
+    let mut chars = vec![];
+    for (i, piece) in pieces.iter().enumerate() {
+        match *piece {
+            Piece::Text(ref text) => chars.extend(text.chars().map(|c| (c, Some(i)))),
+            Piece::Other(c) => chars.push((c, None)),
+        }
+    }
+
+    let quotes = find_quotes(&chars);
+
+    let mut texts = vec![String::new(); pieces.len()];
+    for (&(c, piece), quote) in chars.iter().zip(quotes) {
+        if let Some(i) = piece {
+            match quote {
+                Some(Quote::DoubleOpen) => texts[i].push_str(&quote_style.double_open),
+                Some(Quote::DoubleClose) => texts[i].push_str(&quote_style.double_close),
+                Some(Quote::SingleOpen) => texts[i].push_str(&quote_style.single_open),
+                Some(Quote::SingleClose) => texts[i].push_str(&quote_style.single_close),
+                Some(Quote::Apostrophe) => texts[i].push_str(APOSTROPHE),
+                None => texts[i].push(c),
+            }
+        }
+    }
+
+    for (piece, text) in pieces.into_iter().zip(texts) {
+        if let Piece::Text(original) = piece {
+            *original = text
+                .replace("...", "\u{2026}")
+                .replace("---", "\u{2014}")
+                .replace("--", "\u{2013}");
+        }
+    }
+    spans
+}
+
+fn collect_pieces<'a>(spans: &'a mut [Span], pieces: &mut Vec<Piece<'a>>) {
+    for span in spans {
+        match *span {
+            Text(ref mut text) => pieces.push(Piece::Text(text)),
+            Emphasis(ref mut content)
+            | Strong(ref mut content)
+            | Strikethrough(ref mut content)
+            | Superscript(ref mut content)
+            | Subscript(ref mut content)
+            | Mark(ref mut content)
+            | Link(ref mut content, _, _, _)
+            | RefLink(ref mut content, _, _)
+            | InlineDirective(_, ref mut content, _) => collect_pieces(content, pieces),
+            // escaped chars are kept as they are
+            Literal(c) => pieces.push(Piece::Other(c)),
+            Break | SoftBreak => pieces.push(Piece::Other('\n')),
+            // inline tags don't separate words
+            Html(_) => {}
+            // code, images and the like count as a word
+            _ => pieces.push(Piece::Other('x')),
+        }
+    }
+}
+
+// Decides which of the straight quotes in the text are opening or closing
+// quotes and which are apostrophes. Only quotes in text are changed.
+fn find_quotes(chars: &[(char, Option<usize>)]) -> Vec<Option<Quote>> {
+    let mut quotes = vec![None; chars.len()];
+    // indices of the opening quotes that are still waiting to be closed
+    let mut open: Vec<usize> = vec![];
+
+    for (i, &(c, piece)) in chars.iter().enumerate() {
+        if piece.is_none() || (c != '"' && c != '\'') {
+            continue;
+        }
+        let prev = if i > 0 { Some(chars[i - 1].0) } else { None };
+        let next = chars.get(i + 1).map(|&(c, _)| c);
+
+        // the flanking rules of CommonMark's emphasis delimiters
+        let left_flanking =
+            !is_space(next) && (!is_punctuation(next) || is_space(prev) || is_punctuation(prev));
+        let right_flanking =
+            !is_space(prev) && (!is_punctuation(prev) || is_space(next) || is_punctuation(next));
+
+        let (opening, closing) = if c == '"' {
+            (Quote::DoubleOpen, Quote::DoubleClose)
+        } else {
+            (Quote::SingleOpen, Quote::SingleClose)
+        };
+
+        let matching = if right_flanking {
+            open.iter().rposition(|&j| quotes[j] == Some(opening))
+        } else {
+            None
+        };
+
+        if c == '\'' && is_alphanumeric(prev) && is_alphanumeric(next) {
+            // don't, it's
+            quotes[i] = Some(Quote::Apostrophe);
+        } else if let Some(pos) = matching {
+            quotes[i] = Some(closing);
+            // quotes opened in between are never closed
+            for j in open.drain(pos..).skip(1) {
+                unclosed(&mut quotes[j]);
+            }
+        } else if left_flanking && c == '\'' && next.is_some_and(|c| c.is_ascii_digit()) {
+            // '90s
+            quotes[i] = Some(Quote::Apostrophe);
+        } else if left_flanking {
+            quotes[i] = Some(opening);
+            open.push(i);
+        } else if c == '\'' {
+            // the dogs' bones
+            quotes[i] = Some(Quote::Apostrophe);
+        } else if right_flanking {
+            quotes[i] = Some(closing);
+        }
+    }
+
+    for j in open {
+        unclosed(&mut quotes[j]);
+    }
+    quotes
+}
+
+// single quotes that are never closed are apostrophes, like in 'tis
+fn unclosed(quote: &mut Option<Quote>) {
+    if *quote == Some(Quote::SingleOpen) {
+        *quote = Some(Quote::Apostrophe);
+    }
+}
+
+fn is_space(c: Option<char>) -> bool {
+    match c {
+        Some(c) => c.is_whitespace(),
+        None => true,
+    }
+}
+
+fn is_punctuation(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_ascii_punctuation() || "\u{2013}\u{2014}\u{2026}".contains(c))
+}
+
+fn is_alphanumeric(c: Option<char>) -> bool {
+    c.is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod test {
+    use super::smarten;
+    use options::QuoteStyle;
+    use parser::Span::{Code, Emphasis, Literal, Text};
+
+    fn smarten_text(text: &str) -> String {
+        match smarten(vec![Text(text.to_owned())], &QuoteStyle::english()).pop() {
+            Some(Text(text)) => text,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn pairs_quotes() {
+        assert_eq!(
+            smarten_text(r#""Hello," she said, "it's 'fine'.""#),
+            "\u{201c}Hello,\u{201d} she said, \u{201c}it\u{2019}s \u{2018}fine\u{2019}.\u{201d}"
+        );
+        assert_eq!(
+            smarten_text("(\"nested 'quotes'\")"),
+            "(\u{201c}nested \u{2018}quotes\u{2019}\u{201d})"
+        );
+    }
+
+    #[test]
+    fn finds_apostrophes() {
+        assert_eq!(
+            smarten_text("in the '90s, 'tis the season"),
+            "in the \u{2019}90s, \u{2019}tis the season"
+        );
+        assert_eq!(smarten_text("the dogs' day"), "the dogs\u{2019} day");
+        assert_eq!(smarten_text("don't"), "don\u{2019}t");
+    }
+
+    #[test]
+    fn replaces_dashes_and_ellipses() {
+        assert_eq!(
+            smarten_text("1--2, wait---what...?"),
+            "1\u{2013}2, wait\u{2014}what\u{2026}?"
+        );
+    }
+
+    #[test]
+    fn pairs_quotes_across_spans() {
+        assert_eq!(
+            smarten(
+                vec![
+                    Text("\"".to_owned()),
+                    Emphasis(vec![Text("Hi".to_owned())]),
+                    Text("\" and '".to_owned()),
+                    Code("x".to_owned()),
+                    Text("'".to_owned())
+                ],
+                &QuoteStyle::german()
+            ),
+            vec![
+                Text("\u{201e}".to_owned()),
+                Emphasis(vec![Text("Hi".to_owned())]),
+                Text("\u{201c} and \u{201a}".to_owned()),
+                Code("x".to_owned()),
+                Text("\u{2018}".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_code_and_literals() {
+        assert_eq!(
+            smarten(
+                vec![
+                    Code("\"a\" -- b...".to_owned()),
+                    Text(" ".to_owned()),
+                    Literal('"'),
+                    Text("c".to_owned()),
+                    Literal('"')
+                ],
+                &QuoteStyle::french()
+            ),
+            vec![
+                Code("\"a\" -- b...".to_owned()),
+                Text(" ".to_owned()),
+                Literal('"'),
+                Text("c".to_owned()),
+                Literal('"')
+            ]
+        );
+    }
+}
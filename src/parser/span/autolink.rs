@@ -0,0 +1,235 @@
+use parser::Span;
+use parser::Span::AutoLink;
+use regex::Regex;
+
+pub fn parse_autolink(text: &str) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref AUTOLINK_URI: Regex =
+            Regex::new(r"^<(?P<url>[a-zA-Z][a-zA-Z0-9+.-]{1,31}:[^\s<>]*)>").unwrap();
+        static ref AUTOLINK_EMAIL: Regex = Regex::new(
+            r"^<(?P<email>[a-zA-Z0-9.!#$%&'*+/=?^_`{|}~-]+@[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?(?:\.[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?)*)>"
+        )
+        .unwrap();
+    }
+
+    if let Some(caps) = AUTOLINK_URI.captures(text) {
+        let url = caps.name("url").unwrap().as_str();
+        return Some((
+            AutoLink(url.to_owned(), url.to_owned(), caps[0].to_owned()),
+            caps[0].len(),
+        ));
+    } else if let Some(caps) = AUTOLINK_EMAIL.captures(text) {
+        let email = caps.name("email").unwrap().as_str();
+        return Some((
+            AutoLink(
+                email.to_owned(),
+                format!("mailto:{}", email),
+                caps[0].to_owned(),
+            ),
+            caps[0].len(),
+        ));
+    }
+    None
+}
+
+// Parses a bare URL or email address at the start of the text, following
+// the rules of GitHub's extended autolinks. Whether it starts a word has
+// to be checked by the caller.
+pub fn parse_extended_autolink(text: &str) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref EXTENDED_AUTOLINK: Regex = Regex::new(
+            r"^(?:(?P<url>(?:https?://|www\.)[a-zA-Z0-9_-]+(?:\.[a-zA-Z0-9_-]+)*[^\s<]*)|(?P<email>[a-zA-Z0-9.+_-]+@[a-zA-Z0-9_-]+(?:\.[a-zA-Z0-9_-]+)+))"
+        )
+        .unwrap();
+    }
+
+    let caps = EXTENDED_AUTOLINK.captures(text)?;
+    let (link, url) = if caps.name("url").is_some() {
+        let link = trim_url(&caps[0]);
+        if link.starts_with("www.") {
+            (link, format!("http://{}", link))
+        } else {
+            (link, link.to_owned())
+        }
+    } else {
+        let link = caps[0].trim_end_matches('.');
+        if link.ends_with('-') || link.ends_with('_') {
+            return None;
+        }
+        (link, format!("mailto:{}", link))
+    };
+    Some((AutoLink(link.to_owned(), url, link.to_owned()), link.len()))
+}
+
+// whether an extended autolink may start after the given char, which is
+// at the beginning of a line, after whitespace or after one of the
+// delimiters *, _, ~ and (
+pub fn starts_extended_autolink(prev: Option<char>) -> bool {
+    match prev {
+        None | Some('*') | Some('_') | Some('~') | Some('(') => true,
+        Some(c) => c.is_whitespace(),
+    }
+}
+
+// removes trailing punctuation and unbalanced closing parentheses from a URL
+fn trim_url(mut url: &str) -> &str {
+    loop {
+        if url.ends_with(|c| "?!.,:*_~".contains(c))
+            || (url.ends_with(')') && url.matches(')').count() > url.matches('(').count())
+        {
+            url = &url[..url.len() - 1];
+        } else {
+            return url;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_autolink;
+    use parser::span::parse_spans;
+    use parser::Span;
+    use parser::Span::{AutoLink, Emphasis, Link, Text};
+
+    #[test]
+    fn finds_autolink() {
+        assert_eq!(
+            parse_autolink("<https://example.com> test"),
+            Some((
+                AutoLink(
+                    "https://example.com".to_owned(),
+                    "https://example.com".to_owned(),
+                    "<https://example.com>".to_owned()
+                ),
+                21
+            ))
+        );
+
+        assert_eq!(
+            parse_autolink("<irc://foo.bar:2233/baz?a=b_c>"),
+            Some((
+                AutoLink(
+                    "irc://foo.bar:2233/baz?a=b_c".to_owned(),
+                    "irc://foo.bar:2233/baz?a=b_c".to_owned(),
+                    "<irc://foo.bar:2233/baz?a=b_c>".to_owned()
+                ),
+                30
+            ))
+        );
+
+        assert_eq!(
+            parse_autolink("<foo+bar@example.com>"),
+            Some((
+                AutoLink(
+                    "foo+bar@example.com".to_owned(),
+                    "mailto:foo+bar@example.com".to_owned(),
+                    "<foo+bar@example.com>".to_owned()
+                ),
+                21
+            ))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_autolink("<https://example.com test>"), None);
+        assert_eq!(parse_autolink("<m:abc>"), None);
+        assert_eq!(parse_autolink("<example.com>"), None);
+        assert_eq!(parse_autolink("<foo@bar@example.com>"), None);
+        assert_eq!(parse_autolink("<div>"), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_autolink("test <https://example.com>"), None);
+    }
+
+    fn parse_extended_spans(text: &str) -> Vec<Span> {
+        parse_spans(text, true)
+    }
+
+    #[test]
+    fn finds_extended_autolinks() {
+        assert_eq!(
+            parse_extended_spans("Visit www.example.com/a_b, or https://example.com/(x)."),
+            vec![
+                Text("Visit ".to_owned()),
+                AutoLink(
+                    "www.example.com/a_b".to_owned(),
+                    "http://www.example.com/a_b".to_owned(),
+                    "www.example.com/a_b".to_owned()
+                ),
+                Text(", or ".to_owned()),
+                AutoLink(
+                    "https://example.com/(x)".to_owned(),
+                    "https://example.com/(x)".to_owned(),
+                    "https://example.com/(x)".to_owned()
+                ),
+                Text(".".to_owned())
+            ]
+        );
+
+        assert_eq!(
+            parse_extended_spans("(mail foo.bar@example.com.)"),
+            vec![
+                Text("(mail ".to_owned()),
+                AutoLink(
+                    "foo.bar@example.com".to_owned(),
+                    "mailto:foo.bar@example.com".to_owned(),
+                    "foo.bar@example.com".to_owned()
+                ),
+                Text(".)".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn no_false_extended_autolinks() {
+        assert_eq!(
+            parse_extended_spans("nowww.example.com and foo@bar_"),
+            vec![Text("nowww.example.com and foo@bar_".to_owned())]
+        );
+        assert_eq!(
+            parse_extended_spans("a@b.c-"),
+            vec![Text("a@b.c-".to_owned())]
+        );
+        assert_eq!(
+            parse_extended_spans("[https://x.org](y)"),
+            vec![Link(
+                vec![Text("https://x.org".to_owned())],
+                "y".to_owned(),
+                None,
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn keeps_emphasis_chars_in_urls() {
+        assert_eq!(
+            parse_extended_spans("see https://x.org/*foo*bar and *www.x.org*"),
+            vec![
+                Text("see ".to_owned()),
+                AutoLink(
+                    "https://x.org/*foo*bar".to_owned(),
+                    "https://x.org/*foo*bar".to_owned(),
+                    "https://x.org/*foo*bar".to_owned()
+                ),
+                Text(" and ".to_owned()),
+                Emphasis(vec![AutoLink(
+                    "www.x.org".to_owned(),
+                    "http://www.x.org".to_owned(),
+                    "www.x.org".to_owned()
+                )])
+            ]
+        );
+        assert_eq!(
+            parse_spans("https://x.org/*foo*bar", false),
+            vec![
+                Text("https://x.org/".to_owned()),
+                Emphasis(vec![Text("foo".to_owned())]),
+                Text("bar".to_owned())
+            ]
+        );
+    }
+}
@@ -10,6 +10,10 @@ pub fn parse_break(text: &str) -> Option<(Span, usize)> {
     if BR.is_match(text) {
         return Some((Break, 2));
     }
+    // like in CommonMark, a backslash at the end of a line is a break as well
+    if text == "\\" {
+        return Some((Break, 1));
+    }
     None
 }
 
@@ -21,6 +25,7 @@ mod test {
     #[test]
     fn finds_breaks() {
         assert_eq!(parse_break("  "), Some((Break, 2)));
+        assert_eq!(parse_break("\\"), Some((Break, 1)));
     }
 
     #[test]
@@ -28,5 +33,7 @@ mod test {
         assert_eq!(parse_break("this is a test  "), None);
         assert_eq!(parse_break(" "), None);
         assert_eq!(parse_break("  a"), None);
+        assert_eq!(parse_break("\\ "), None);
+        assert_eq!(parse_break("\\a"), None);
     }
 }
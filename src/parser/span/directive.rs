@@ -0,0 +1,159 @@
+use parser::attributes::parse_attributes;
+use parser::span::parse_spans;
+use parser::Span::InlineDirective;
+use parser::{Attributes, Span};
+use regex::Regex;
+
+pub fn parse_inline_directive(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
+    if !text.starts_with(':') {
+        return None;
+    }
+    let (name, label, attributes, len) = parse_directive_head(&text[1..])?;
+    // a bare `:name` is too common in normal text
+    if label.is_none() && attributes.is_none() {
+        return None;
+    }
+    Some((
+        InlineDirective(
+            name.to_owned(),
+            parse_spans(label.unwrap_or_default(), extended_autolinks),
+            attributes,
+        ),
+        len + 1,
+    ))
+}
+
+// Parses the name, [label] and {attributes} of a directive like
+// `youtube[Talk]{id=abc}` that come after the colons and returns them
+// along with their length. Brackets in the label have to be balanced.
+pub fn parse_directive_head(text: &str) -> Option<(&str, Option<&str>, Option<Attributes>, usize)> {
+    lazy_static! {
+        static ref NAME: Regex = Regex::new(r"^[A-Za-z][A-Za-z0-9_-]*").unwrap();
+    }
+
+    let name = NAME.find(text)?.as_str();
+    let mut len = name.len();
+
+    let label = if text[len..].starts_with('[') {
+        let mut depth = 0;
+        let mut end = None;
+        let mut escaped = false;
+        for (i, c) in text[len..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let end = len + end?;
+        let label = &text[len + 1..end];
+        len = end + 1;
+        Some(label)
+    } else {
+        None
+    };
+
+    let attributes = match parse_attributes(&text[len..]) {
+        Some((attributes, attributes_len)) => {
+            len += attributes_len;
+            Some(attributes)
+        }
+        None => None,
+    };
+
+    Some((name, label, attributes, len))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_inline_directive;
+    use parser::Attributes;
+    use parser::Span::{Emphasis, InlineDirective, Link, Text};
+
+    #[test]
+    fn finds_inline_directive() {
+        assert_eq!(
+            parse_inline_directive(":kbd[Ctrl] test", false),
+            Some((
+                InlineDirective("kbd".to_owned(), vec![Text("Ctrl".to_owned())], None),
+                10
+            ))
+        );
+
+        assert_eq!(
+            parse_inline_directive(":abbr[*HTML*]{title=\"HyperText\"} test", false),
+            Some((
+                InlineDirective(
+                    "abbr".to_owned(),
+                    vec![Emphasis(vec![Text("HTML".to_owned())])],
+                    Some(Attributes {
+                        id: None,
+                        classes: vec![],
+                        pairs: vec![("title".to_owned(), "HyperText".to_owned())]
+                    })
+                ),
+                32
+            ))
+        );
+
+        assert_eq!(
+            parse_inline_directive(":badge{.new}", false),
+            Some((
+                InlineDirective(
+                    "badge".to_owned(),
+                    vec![],
+                    Some(Attributes {
+                        id: None,
+                        classes: vec!["new".to_owned()],
+                        pairs: vec![]
+                    })
+                ),
+                12
+            ))
+        );
+    }
+
+    #[test]
+    fn balances_brackets() {
+        assert_eq!(
+            parse_inline_directive(":cite[see [docs](/docs)] test", false),
+            Some((
+                InlineDirective(
+                    "cite".to_owned(),
+                    vec![
+                        Text("see ".to_owned()),
+                        Link(
+                            vec![Text("docs".to_owned())],
+                            "/docs".to_owned(),
+                            None,
+                            None
+                        )
+                    ],
+                    None
+                ),
+                24
+            ))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_inline_directive(":kbd test", false), None);
+        assert_eq!(parse_inline_directive(":kbd[Ctrl test", false), None);
+        assert_eq!(parse_inline_directive(":1[Ctrl]", false), None);
+        assert_eq!(parse_inline_directive(": kbd[Ctrl]", false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_inline_directive("press :kbd[Ctrl]", false), None);
+    }
+}
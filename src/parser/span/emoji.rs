@@ -0,0 +1,135 @@
+use parser::span::emoji_table::EMOJI;
+use parser::Attributes;
+use parser::Span;
+use parser::Span::{Image, Text};
+use regex::Regex;
+use std::collections::HashMap;
+
+// replaces emoji shortcodes like `:tada:` in the text with the emoji they stand for.
+// Shortcodes in `custom` take precedence and become images of the given url.
+pub fn expand_emoji(text: &str, custom: &HashMap<String, String>) -> Vec<Span> {
+    lazy_static! {
+        static ref SHORTCODE: Regex = Regex::new(r"^:(?P<name>[a-z0-9_+-]+):").unwrap();
+    }
+
+    let mut spans = vec![];
+    let mut t = String::new();
+    let mut i = 0;
+    while let Some(pos) = text[i..].find(':') {
+        let start = i + pos;
+        t.push_str(&text[i..start]);
+        i = start + 1;
+
+        if let Some(caps) = SHORTCODE.captures(&text[start..]) {
+            let name = caps.name("name").unwrap().as_str();
+            if let Some(url) = custom.get(name) {
+                if !t.is_empty() {
+                    spans.push(Text(t));
+                    t = String::new();
+                }
+                spans.push(Image(
+                    caps[0].to_owned(),
+                    url.to_owned(),
+                    Some(caps[0].to_owned()),
+                    Some(Attributes {
+                        classes: vec!["emoji".to_owned()],
+                        ..Default::default()
+                    }),
+                ));
+                i = start + caps[0].len();
+                continue;
+            }
+            if let Ok(index) = EMOJI.binary_search_by(|&(shortcode, _)| shortcode.cmp(name)) {
+                t.push_str(EMOJI[index].1);
+                i = start + caps[0].len();
+                continue;
+            }
+        }
+        // not a shortcode, the colon may still start the next one
+        t.push(':');
+    }
+    t.push_str(&text[i..]);
+    if !t.is_empty() {
+        spans.push(Text(t));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod test {
+    use super::expand_emoji;
+    use parser::span::emoji_table::EMOJI;
+    use parser::Attributes;
+    use parser::Span::{Image, Text};
+    use std::collections::HashMap;
+
+    #[test]
+    fn expands_emoji() {
+        assert_eq!(
+            expand_emoji(
+                ":white_check_mark: done, :warning: careful",
+                &HashMap::new()
+            ),
+            vec![Text("\u{2705} done, \u{26a0}\u{fe0f} careful".to_owned())]
+        );
+        assert_eq!(
+            expand_emoji("a:+1::-1:b", &HashMap::new()),
+            vec![Text("a\u{1f44d}\u{1f44e}b".to_owned())]
+        );
+        assert_eq!(
+            expand_emoji("at 10:30: :unknown:tada:", &HashMap::new()),
+            vec![Text("at 10:30: :unknown\u{1f389}".to_owned())]
+        );
+        assert_eq!(
+            expand_emoji(":stop_sign: :technologist:", &HashMap::new()),
+            vec![Text("\u{1f6d1} \u{1f9d1}\u{200d}\u{1f4bb}".to_owned())]
+        );
+    }
+
+    #[test]
+    fn expands_custom_emoji() {
+        let mut custom = HashMap::new();
+        custom.insert("shipit".to_owned(), "/emoji/shipit.png".to_owned());
+        custom.insert("tada".to_owned(), "/emoji/tada.png".to_owned());
+
+        assert_eq!(
+            expand_emoji("go :shipit: :tada::smile:", &custom),
+            vec![
+                Text("go ".to_owned()),
+                Image(
+                    ":shipit:".to_owned(),
+                    "/emoji/shipit.png".to_owned(),
+                    Some(":shipit:".to_owned()),
+                    Some(Attributes {
+                        classes: vec!["emoji".to_owned()],
+                        ..Default::default()
+                    })
+                ),
+                Text(" ".to_owned()),
+                Image(
+                    ":tada:".to_owned(),
+                    "/emoji/tada.png".to_owned(),
+                    Some(":tada:".to_owned()),
+                    Some(Attributes {
+                        classes: vec!["emoji".to_owned()],
+                        ..Default::default()
+                    })
+                ),
+                Text("\u{1f604}".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(
+            expand_emoji(":Smile: : smile: ::", &HashMap::new()),
+            vec![Text(":Smile: : smile: ::".to_owned())]
+        );
+    }
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(EMOJI.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
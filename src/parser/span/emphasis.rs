@@ -3,20 +3,21 @@ use parser::Span;
 use parser::Span::Emphasis;
 use regex::Regex;
 
-pub fn parse_emphasis(text: &str) -> Option<(Span, usize)> {
+pub fn parse_emphasis(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
     lazy_static! {
-        static ref EMPHASIS_UNDERSCORE: Regex = Regex::new(r"^_(?P<text>.+?)_").unwrap();
+        static ref EMPHASIS_UNDERSCORE: Regex =
+            Regex::new(r"^_(?P<text>.+?)_(?:[^A-Za-z0-9]|$)").unwrap();
         static ref EMPHASIS_STAR: Regex = Regex::new(r"^\*(?P<text>.+?)\*").unwrap();
     }
 
     if EMPHASIS_UNDERSCORE.is_match(text) {
         let caps = EMPHASIS_UNDERSCORE.captures(text).unwrap();
         let t = caps.name("text").unwrap().as_str();
-        return Some((Emphasis(parse_spans(t)), t.len() + 2));
+        return Some((Emphasis(parse_spans(t, extended_autolinks)), t.len() + 2));
     } else if EMPHASIS_STAR.is_match(text) {
         let caps = EMPHASIS_STAR.captures(text).unwrap();
         let t = caps.name("text").unwrap().as_str();
-        return Some((Emphasis(parse_spans(t)), t.len() + 2));
+        return Some((Emphasis(parse_spans(t, extended_autolinks)), t.len() + 2));
     }
     None
 }
@@ -29,45 +30,51 @@ mod test {
     #[test]
     fn finds_emphasis() {
         assert_eq!(
-            parse_emphasis("_testing things_ test"),
+            parse_emphasis("_testing things_ test", false),
             Some((Emphasis(vec![Text("testing things".to_owned())]), 16))
         );
 
         assert_eq!(
-            parse_emphasis("*testing things* test"),
+            parse_emphasis("*testing things* test", false),
             Some((Emphasis(vec![Text("testing things".to_owned())]), 16))
         );
 
         assert_eq!(
-            parse_emphasis("_testing things_ things_ test"),
+            parse_emphasis("_testing things_ things_ test", false),
             Some((Emphasis(vec![Text("testing things".to_owned())]), 16))
         );
 
         assert_eq!(
-            parse_emphasis("_w_ things_ test"),
+            parse_emphasis("_w_ things_ test", false),
             Some((Emphasis(vec![Text("w".to_owned())]), 3))
         );
 
         assert_eq!(
-            parse_emphasis("*w* things* test"),
+            parse_emphasis("*w* things* test", false),
             Some((Emphasis(vec![Text("w".to_owned())]), 3))
         );
 
         assert_eq!(
-            parse_emphasis("_w__ testing things test"),
+            parse_emphasis("_w__ testing things test", false),
             Some((Emphasis(vec![Text("w".to_owned())]), 3))
         );
+
+        assert_eq!(
+            parse_emphasis("_snake_case_ test", false),
+            Some((Emphasis(vec![Text("snake_case".to_owned())]), 12))
+        );
     }
 
     #[test]
     fn no_false_positives() {
-        assert_eq!(parse_emphasis("__ testing things test"), None);
-        assert_eq!(parse_emphasis("_ test"), None);
+        assert_eq!(parse_emphasis("__ testing things test", false), None);
+        assert_eq!(parse_emphasis("_ test", false), None);
+        assert_eq!(parse_emphasis("_snake_case", false), None);
     }
 
     #[test]
     fn no_early_matching() {
-        assert_eq!(parse_emphasis("were _testing things_ test"), None);
-        assert_eq!(parse_emphasis("were *testing things* test"), None);
+        assert_eq!(parse_emphasis("were _testing things_ test", false), None);
+        assert_eq!(parse_emphasis("were *testing things* test", false), None);
     }
 }
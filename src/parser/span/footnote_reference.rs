@@ -0,0 +1,48 @@
+use parser::Span;
+use parser::Span::FootnoteReference;
+use regex::Regex;
+
+pub fn parse_footnote_reference(text: &str) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref FOOTNOTE_REFERENCE: Regex = Regex::new(r"^\[\^(?P<id>[^\]\s]+)\]").unwrap();
+    }
+
+    if FOOTNOTE_REFERENCE.is_match(text) {
+        let caps = FOOTNOTE_REFERENCE.captures(text).unwrap();
+        let id = caps.name("id").unwrap().as_str();
+        return Some((FootnoteReference(id.to_owned()), id.len() + 3));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_footnote_reference;
+    use parser::Span::FootnoteReference;
+
+    #[test]
+    fn finds_footnote_reference() {
+        assert_eq!(
+            parse_footnote_reference("[^1] test"),
+            Some((FootnoteReference("1".to_owned()), 4))
+        );
+
+        assert_eq!(
+            parse_footnote_reference("[^a-Note] test [^b]"),
+            Some((FootnoteReference("a-Note".to_owned()), 9))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_footnote_reference("[^] test"), None);
+        assert_eq!(parse_footnote_reference("[^a note] test"), None);
+        assert_eq!(parse_footnote_reference("[note] test"), None);
+        assert_eq!(parse_footnote_reference("^[note] test"), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_footnote_reference("test [^1]"), None);
+    }
+}
@@ -0,0 +1,73 @@
+use parser::Span;
+use parser::Span::Html;
+use regex::Regex;
+
+pub fn parse_html(text: &str) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref HTML: Regex = Regex::new(
+            r#"^(?:<[A-Za-z][A-Za-z0-9-]*(?:\s+[a-zA-Z_:][a-zA-Z0-9_.:-]*(?:\s*=\s*(?:[^\s"'=<>`]+|'[^']*'|"[^"]*"))?)*\s*/?>|</[A-Za-z][A-Za-z0-9-]*\s*>|<!--.*?-->|<\?.*?\?>|<![A-Za-z][^>]*>|<!\[CDATA\[.*?\]\]>)"#
+        )
+        .unwrap();
+    }
+
+    if let Some(mat) = HTML.find(text) {
+        return Some((Html(mat.as_str().to_owned()), mat.end()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_html;
+    use parser::Span::Html;
+
+    #[test]
+    fn finds_tags() {
+        assert_eq!(
+            parse_html("<span class=\"a\" data-x='y' hidden> test"),
+            Some((Html("<span class=\"a\" data-x='y' hidden>".to_owned()), 34))
+        );
+        assert_eq!(
+            parse_html("</span> test"),
+            Some((Html("</span>".to_owned()), 7))
+        );
+        assert_eq!(
+            parse_html("<br/> test"),
+            Some((Html("<br/>".to_owned()), 5))
+        );
+    }
+
+    #[test]
+    fn finds_comments_and_declarations() {
+        assert_eq!(
+            parse_html("<!-- a -- comment --> test -->"),
+            Some((Html("<!-- a -- comment -->".to_owned()), 21))
+        );
+        assert_eq!(
+            parse_html("<?php echo 1; ?> test"),
+            Some((Html("<?php echo 1; ?>".to_owned()), 16))
+        );
+        assert_eq!(
+            parse_html("<!ELEMENT br EMPTY> test"),
+            Some((Html("<!ELEMENT br EMPTY>".to_owned()), 19))
+        );
+        assert_eq!(
+            parse_html("<![CDATA[a > b]]> test"),
+            Some((Html("<![CDATA[a > b]]>".to_owned()), 17))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_html("< span>"), None);
+        assert_eq!(parse_html("<3 things>"), None);
+        assert_eq!(parse_html("<a href=\"x>"), None);
+        assert_eq!(parse_html("<https://example.com>"), None);
+        assert_eq!(parse_html("a < b"), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_html("test <span>"), None);
+    }
+}
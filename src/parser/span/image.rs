@@ -1,5 +1,6 @@
+use parser::attributes::parse_attributes;
 use parser::Span;
-use parser::Span::Image;
+use parser::Span::{Image, RefImage};
 use regex::Regex;
 
 pub fn parse_image(text: &str) -> Option<(Span, usize)> {
@@ -7,11 +8,13 @@ pub fn parse_image(text: &str) -> Option<(Span, usize)> {
         static ref IMAGE: Regex =
             Regex::new("^!\\[(?P<text>.*?)\\]\\((?P<url>.*?)(?:\\s\"(?P<title>.*?)\")?\\)")
                 .unwrap();
+        static ref REF_IMAGE: Regex =
+            Regex::new("^!\\[(?P<text>.*?)\\](?:\\[(?P<ref>.*?)\\])?").unwrap();
     }
 
     if IMAGE.is_match(text) {
         let caps = IMAGE.captures(text).unwrap();
-        let text = if let Some(mat) = caps.name("text") {
+        let alt = if let Some(mat) = caps.name("text") {
             mat.as_str().to_owned()
         } else {
             "".to_owned()
@@ -21,14 +24,26 @@ pub fn parse_image(text: &str) -> Option<(Span, usize)> {
         } else {
             "".to_owned()
         };
-        let title = if let Some(mat) = caps.name("title") {
-            Some(mat.as_str().to_owned())
-        } else {
-            None
-        };
+        let title = caps.name("title").map(|mat| mat.as_str().to_owned());
         // TODO correctly get whitespace length between url and title
-        let len = text.len() + url.len() + 5 + title.clone().map_or(0, |t| t.len() + 3);
-        return Some((Image(text, url, title), len));
+        let mut len = alt.len() + url.len() + 5 + title.clone().map_or(0, |t| t.len() + 3);
+        let attributes = parse_attributes(&text[len..]).map(|(attributes, attr_len)| {
+            len += attr_len;
+            attributes
+        });
+        return Some((Image(alt, url, title, attributes), len));
+    }
+
+    // Without an explicit reference, like in `![alt]` or `![alt][]`, the
+    // HTML generating code will try to match the alt text as reference.
+    if let Some(caps) = REF_IMAGE.captures(text) {
+        let alt = caps["text"].to_owned();
+        let reference = caps
+            .name("ref")
+            .map_or("", |mat| mat.as_str())
+            .trim()
+            .to_lowercase();
+        return Some((RefImage(alt, reference, caps[0].to_owned()), caps[0].len()));
     }
     None
 }
@@ -38,24 +53,35 @@ fn finds_image() {
     assert_eq!(
         parse_image("![an example](example.com) test"),
         Some((
-            Image("an example".to_owned(), "example.com".to_owned(), None),
+            Image(
+                "an example".to_owned(),
+                "example.com".to_owned(),
+                None,
+                None
+            ),
             26
         ))
     );
 
     assert_eq!(
         parse_image("![](example.com) test"),
-        Some((Image("".to_owned(), "example.com".to_owned(), None), 16))
+        Some((
+            Image("".to_owned(), "example.com".to_owned(), None, None),
+            16
+        ))
     );
 
     assert_eq!(
         parse_image("![an example]() test"),
-        Some((Image("an example".to_owned(), "".to_owned(), None), 15))
+        Some((
+            Image("an example".to_owned(), "".to_owned(), None, None),
+            15
+        ))
     );
 
     assert_eq!(
         parse_image("![]() test"),
-        Some((Image("".to_owned(), "".to_owned(), None), 5))
+        Some((Image("".to_owned(), "".to_owned(), None, None), 5))
     );
 
     assert_eq!(
@@ -64,7 +90,8 @@ fn finds_image() {
             Image(
                 "an example".to_owned(),
                 "example.com".to_owned(),
-                Some("Title".to_owned())
+                Some("Title".to_owned()),
+                None
             ),
             34
         ))
@@ -73,16 +100,81 @@ fn finds_image() {
     assert_eq!(
         parse_image("![an example](example.com) test [a link](example.com)"),
         Some((
-            Image("an example".to_owned(), "example.com".to_owned(), None),
+            Image(
+                "an example".to_owned(),
+                "example.com".to_owned(),
+                None,
+                None
+            ),
             26
         ))
     );
 }
 
+#[test]
+fn finds_attributes() {
+    use parser::Attributes;
+
+    assert_eq!(
+        parse_image("![logo](a.png){width=120} test"),
+        Some((
+            Image(
+                "logo".to_owned(),
+                "a.png".to_owned(),
+                None,
+                Some(Attributes {
+                    pairs: vec![("width".to_owned(), "120".to_owned())],
+                    ..Default::default()
+                })
+            ),
+            25
+        ))
+    );
+}
+
+#[test]
+fn finds_reference_image() {
+    assert_eq!(
+        parse_image("![an example][Logo] test"),
+        Some((
+            RefImage(
+                "an example".to_owned(),
+                "logo".to_owned(),
+                "![an example][Logo]".to_owned()
+            ),
+            19
+        ))
+    );
+
+    assert_eq!(
+        parse_image("![logo][] test"),
+        Some((
+            RefImage("logo".to_owned(), "".to_owned(), "![logo][]".to_owned()),
+            9
+        ))
+    );
+
+    assert_eq!(
+        parse_image("![logo] [photo]"),
+        Some((
+            RefImage("logo".to_owned(), "".to_owned(), "![logo]".to_owned()),
+            7
+        ))
+    );
+
+    assert_eq!(
+        parse_image("![()] testing things test"),
+        Some((
+            RefImage("()".to_owned(), "".to_owned(), "![()]".to_owned()),
+            5
+        ))
+    );
+}
+
 #[test]
 fn no_false_positives() {
-    assert_eq!(parse_image("![()] testing things test"), None);
     assert_eq!(parse_image("!()[] testing things test"), None);
+    assert_eq!(parse_image("! [logo] test"), None);
 }
 
 #[test]
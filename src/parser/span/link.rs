@@ -1,3 +1,4 @@
+use parser::attributes::parse_attributes;
 use parser::span::parse_spans;
 use parser::Span;
 use parser::Span::{Link, RefLink};
@@ -60,18 +61,27 @@ pub fn parse_link(text: &str) -> Option<(Span, usize)> {
         // Check whether we have an inline link (in which case the "url" field is captured),
         // whether there's an explicit reference provided or if we should implicitly use the link
         // content as reference.
+        // links can't contain autolinks, so bare urls in their content are
+        // left as text
         if let Some(url) = caps.name("url") {
             let url = url.as_str().trim().to_owned();
             let title = caps.name("title").map(|t| t.as_str().to_owned());
-            let len = 1 + content.len() + 1 + caps[0].len();
+            let mut len = 1 + content.len() + 1 + caps[0].len();
+            let attributes = parse_attributes(&text[len..]).map(|(attributes, attr_len)| {
+                len += attr_len;
+                attributes
+            });
 
-            return Some((Link(parse_spans(&content), url, title), len));
+            return Some((
+                Link(parse_spans(&content, false), url, title, attributes),
+                len,
+            ));
         } else if let Some(reference) = caps.name("ref") {
             let reference = reference.as_str().trim().to_lowercase();
             let len = 1 + content.len() + 1 + caps[0].len();
             let raw = ["[", &content, "]", &caps[0]].join("");
 
-            return Some((RefLink(parse_spans(&content), reference, raw), len));
+            return Some((RefLink(parse_spans(&content, false), reference, raw), len));
         } else {
             // Leave the reference empty, the HTML generating code will try to match both reference
             // and slugified content.
@@ -79,7 +89,7 @@ pub fn parse_link(text: &str) -> Option<(Span, usize)> {
             let len = 1 + content.len() + 1;
             let raw = ["[", &content, "]"].join("");
 
-            return Some((RefLink(parse_spans(&content), reference, raw), len));
+            return Some((RefLink(parse_spans(&content, false), reference, raw), len));
         }
     }
     None
@@ -88,6 +98,7 @@ pub fn parse_link(text: &str) -> Option<(Span, usize)> {
 #[cfg(test)]
 mod test {
     use parser::span::parse_link;
+    use parser::Attributes;
     use parser::Span::{Image, Link, Literal, RefLink, Text};
 
     #[test]
@@ -98,6 +109,7 @@ mod test {
                 Link(
                     vec![Text("an example".to_owned())],
                     "example.com".to_owned(),
+                    None,
                     None
                 ),
                 25
@@ -118,20 +130,25 @@ mod test {
 
         assert_eq!(
             parse_link("[](example.com) test"),
-            Some((Link(vec![], "example.com".to_owned(), None), 15))
+            Some((Link(vec![], "example.com".to_owned(), None, None), 15))
         );
 
         assert_eq!(
             parse_link("[an example]() test"),
             Some((
-                Link(vec![Text("an example".to_owned())], "".to_owned(), None),
+                Link(
+                    vec![Text("an example".to_owned())],
+                    "".to_owned(),
+                    None,
+                    None
+                ),
                 14
             ))
         );
 
         assert_eq!(
             parse_link("[]() test"),
-            Some((Link(vec![], "".to_owned(), None), 4))
+            Some((Link(vec![], "".to_owned(), None, None), 4))
         );
 
         assert_eq!(
@@ -152,7 +169,8 @@ mod test {
                 Link(
                     vec![Text("an example".to_owned())],
                     "example.com".to_owned(),
-                    Some("Title".to_owned())
+                    Some("Title".to_owned()),
+                    None
                 ),
                 33
             ))
@@ -164,6 +182,7 @@ mod test {
                 Link(
                     vec![Text("an example".to_owned())],
                     "example.com".to_owned(),
+                    None,
                     None
                 ),
                 25
@@ -171,14 +190,48 @@ mod test {
         );
     }
 
+    #[test]
+    fn finds_attributes() {
+        assert_eq!(
+            parse_link("[docs](docs.html){.external target=_blank} test"),
+            Some((
+                Link(
+                    vec![Text("docs".to_owned())],
+                    "docs.html".to_owned(),
+                    None,
+                    Some(Attributes {
+                        id: None,
+                        classes: vec!["external".to_owned()],
+                        pairs: vec![("target".to_owned(), "_blank".to_owned())]
+                    })
+                ),
+                42
+            ))
+        );
+
+        assert_eq!(
+            parse_link("[docs](docs.html) {.external}"),
+            Some((
+                Link(
+                    vec![Text("docs".to_owned())],
+                    "docs.html".to_owned(),
+                    None,
+                    None
+                ),
+                17
+            ))
+        );
+    }
+
     #[test]
     fn brackets_in_link() {
         assert_eq!(
             parse_link("[![test](abc)](example.com) test [a link](example.com)"),
             Some((
                 Link(
-                    vec![Image("test".to_owned(), "abc".to_owned(), None)],
+                    vec![Image("test".to_owned(), "abc".to_owned(), None, None)],
                     "example.com".to_owned(),
+                    None,
                     None
                 ),
                 27
@@ -195,6 +248,7 @@ mod test {
                         Text("wow".to_owned())
                     ],
                     "example.com".to_owned(),
+                    None,
                     None
                 ),
                 23
@@ -207,6 +261,7 @@ mod test {
                 Link(
                     vec![Text("huh".to_owned()), Literal('['), Text("wow".to_owned())],
                     "example.com".to_owned(),
+                    None,
                     None
                 ),
                 23
@@ -221,7 +276,8 @@ mod test {
                 Link(
                     vec![Text("an example".to_owned())],
                     "example.com".to_owned(),
-                    Some("Title (huh!)".to_owned())
+                    Some("Title (huh!)".to_owned()),
+                    None
                 ),
                 40
             ))
@@ -248,7 +304,8 @@ mod test {
                 Link(
                     vec![Text("an example".to_owned())],
                     "example.com".to_owned(),
-                    Some("Title".to_owned())
+                    Some("Title".to_owned()),
+                    None
                 ),
                 43
             ))
@@ -0,0 +1,54 @@
+use parser::span::parse_spans;
+use parser::Span;
+use parser::Span::Mark;
+use regex::Regex;
+
+pub fn parse_mark(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref MARK: Regex = Regex::new(r"^==(?P<text>[^=\s](?:.*?[^=\s])??)==").unwrap();
+    }
+
+    if MARK.is_match(text) {
+        let caps = MARK.captures(text).unwrap();
+        let t = caps.name("text").unwrap().as_str();
+        return Some((Mark(parse_spans(t, extended_autolinks)), t.len() + 4));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_mark;
+    use parser::Span::{Mark, Strong, Text};
+
+    #[test]
+    fn finds_mark() {
+        assert_eq!(
+            parse_mark("==testing things== test", false),
+            Some((Mark(vec![Text("testing things".to_owned())]), 18))
+        );
+
+        assert_eq!(
+            parse_mark("==w== test==", false),
+            Some((Mark(vec![Text("w".to_owned())]), 5))
+        );
+
+        assert_eq!(
+            parse_mark("==**w**== test", false),
+            Some((Mark(vec![Strong(vec![Text("w".to_owned())])]), 9))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_mark("== testing things== test", false), None);
+        assert_eq!(parse_mark("==testing things == test", false), None);
+        assert_eq!(parse_mark("==== test", false), None);
+        assert_eq!(parse_mark("=testing things= test", false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_mark("were ==testing things== test", false), None);
+    }
+}
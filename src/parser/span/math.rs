@@ -0,0 +1,85 @@
+use parser::Span;
+use parser::Span::{InlineDisplayMath, InlineMath};
+use regex::Regex;
+
+pub fn parse_math(text: &str) -> Option<(Span, usize)> {
+    lazy_static! {
+        // the formula can't start or end with whitespace and the closing
+        // dollar can't be followed by a digit, so that prices like $5 and $6
+        // are not mistaken for math
+        static ref MATH: Regex = Regex::new(
+            r"^\$(?P<text>(?:[^\s$\\]|\\.)(?:(?:[^$\\]|\\.)*(?:[^\s$\\]|\\.))?)\$(?P<digit>[0-9])?"
+        )
+        .unwrap();
+        static ref DISPLAY_MATH: Regex =
+            Regex::new(r"^\$\$(?P<text>(?:[^$\\]|\\.)*?[^\s$\\](?:[^$\\]|\\.)*?)\$\$").unwrap();
+    }
+
+    if let Some(caps) = DISPLAY_MATH.captures(text) {
+        let t = caps.name("text").unwrap().as_str();
+        return Some((InlineDisplayMath(t.trim().to_owned()), t.len() + 4));
+    }
+
+    if let Some(caps) = MATH.captures(text) {
+        if caps.name("digit").is_some() {
+            return None;
+        }
+        let t = caps.name("text").unwrap().as_str();
+        return Some((InlineMath(t.to_owned()), t.len() + 2));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_math;
+    use parser::Span::{InlineDisplayMath, InlineMath};
+
+    #[test]
+    fn finds_math() {
+        assert_eq!(
+            parse_math("$x_1 * y_2$ test"),
+            Some((InlineMath("x_1 * y_2".to_owned()), 11))
+        );
+
+        assert_eq!(
+            parse_math("$x$ test"),
+            Some((InlineMath("x".to_owned()), 3))
+        );
+
+        assert_eq!(
+            parse_math(r"$\$5 + \{a\}$ test"),
+            Some((InlineMath(r"\$5 + \{a\}".to_owned()), 13))
+        );
+    }
+
+    #[test]
+    fn finds_display_math() {
+        assert_eq!(
+            parse_math("$$x$$ test"),
+            Some((InlineDisplayMath("x".to_owned()), 5))
+        );
+
+        assert_eq!(
+            parse_math(r"$$ \sum_i a_i \$ $$ test"),
+            Some((InlineDisplayMath(r"\sum_i a_i \$".to_owned()), 19))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_math("$ x$ test"), None);
+        assert_eq!(parse_math("$x $ test"), None);
+        assert_eq!(parse_math("$$ test"), None);
+        assert_eq!(parse_math("$$ $$ test"), None);
+        assert_eq!(parse_math("$$x$ test"), None);
+        assert_eq!(parse_math("$5 and $6"), None);
+        assert_eq!(parse_math("$x$5"), None);
+        assert_eq!(parse_math("$x test"), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_math("test $x$"), None);
+    }
+}
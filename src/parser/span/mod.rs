@@ -1,25 +1,67 @@
 use parser::Span;
 use parser::Span::{Literal, Text};
 
+mod autolink;
 mod br;
 mod code;
+mod directive;
+mod emoji;
+mod emoji_table;
 mod emphasis;
+mod footnote_reference;
+mod html;
 mod image;
 mod link;
+mod mark;
+mod math;
+mod reference;
+mod strikethrough;
 mod strong;
+mod subscript;
+mod superscript;
+mod wiki_link;
+use self::autolink::{parse_autolink, parse_extended_autolink, starts_extended_autolink};
 use self::br::parse_break;
 use self::code::parse_code;
+pub use self::directive::parse_directive_head;
+use self::directive::parse_inline_directive;
+pub use self::emoji::expand_emoji;
 use self::emphasis::parse_emphasis;
+use self::footnote_reference::parse_footnote_reference;
+use self::html::parse_html;
 use self::image::parse_image;
 use self::link::parse_link;
+use self::mark::parse_mark;
+use self::math::parse_math;
+pub use self::reference::find_references;
+use self::strikethrough::parse_strikethrough;
 use self::strong::parse_strong;
+use self::subscript::parse_subscript;
+use self::superscript::parse_superscript;
+use self::wiki_link::parse_wiki_link;
 
-pub fn parse_spans(text: &str) -> Vec<Span> {
+// Bare urls and email addresses are only parsed as autolinks if
+// `extended_autolinks` is set. They are found while parsing the spans, so that
+// e.g. the stars in `https://x.org/*a*b` don't become emphasis.
+pub fn parse_spans(text: &str, extended_autolinks: bool) -> Vec<Span> {
     let mut tokens = vec![];
     let mut t = String::new();
     let mut i = 0;
     while i < text.len() {
-        match parse_span(&text[i..text.len()]) {
+        // underscores inside of words can't start emphasis, e.g. in snake_case,
+        // and colons can't start directives, e.g. in a url like http://a[b]
+        let prev = text[..i].chars().last();
+        let intraword = (text[i..].starts_with('_') || text[i..].starts_with(':'))
+            && prev.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let span = if intraword {
+            None
+        } else if extended_autolinks && starts_extended_autolink(prev) {
+            parse_extended_autolink(&text[i..])
+                .or_else(|| parse_span(&text[i..], extended_autolinks))
+        } else {
+            parse_span(&text[i..text.len()], extended_autolinks)
+        };
+        match span {
             Some((span, consumed_chars)) => {
                 if !t.is_empty() {
                     // if this text is on the very left
@@ -64,24 +106,34 @@ fn parse_escape(text: &str) -> Option<(Span, usize)> {
         return match chars.next() {
             Some(x @ '\\') | Some(x @ '`') | Some(x @ '*') | Some(x @ '_') | Some(x @ '{')
             | Some(x @ '}') | Some(x @ '[') | Some(x @ ']') | Some(x @ '(') | Some(x @ ')')
-            | Some(x @ '#') | Some(x @ '+') | Some(x @ '-') | Some(x @ '.') | Some(x @ '!') => {
-                Some((Literal(x), 2))
-            }
+            | Some(x @ '#') | Some(x @ '+') | Some(x @ '-') | Some(x @ '.') | Some(x @ '!')
+            | Some(x @ '~') | Some(x @ '$') | Some(x @ '^') | Some(x @ '=') | Some(x @ '"')
+            | Some(x @ '\'') => Some((Literal(x), 2)),
             _ => None,
         };
     }
     None
 }
 
-fn parse_span(text: &str) -> Option<(Span, usize)> {
+fn parse_span(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
     pipe_opt!(
     text
     => parse_escape
     => parse_code
-    => parse_strong
-    => parse_emphasis
+    => parse_math
+    => parse_autolink
+    => parse_html
+    => (parse_strong(extended_autolinks))
+    => (parse_emphasis(extended_autolinks))
+    => (parse_strikethrough(extended_autolinks))
+    => (parse_mark(extended_autolinks))
+    => (parse_superscript(extended_autolinks))
+    => (parse_subscript(extended_autolinks))
     => parse_break
     => parse_image
+    => parse_wiki_link
+    => (parse_inline_directive(extended_autolinks))
+    => parse_footnote_reference
     => parse_link
     )
 }
@@ -89,34 +141,88 @@ fn parse_span(text: &str) -> Option<(Span, usize)> {
 #[cfg(test)]
 mod test {
     use parser::span::parse_spans;
-    use parser::Span::{Break, Code, Emphasis, Image, Link, Literal, Strong, Text};
+    use parser::Span::{
+        Break, Code, Emphasis, Image, InlineMath, Link, Literal, Mark, Strikethrough, Strong,
+        Subscript, Superscript, Text,
+    };
     use std::str;
 
     #[test]
     fn converts_into_text() {
         assert_eq!(
-            parse_spans("this is a test"),
+            parse_spans("this is a test", false),
             vec![Text("this is a test".to_owned())]
         );
     }
 
     #[test]
     fn finds_escapes() {
-        assert_eq!(parse_spans(r"\*"), vec![Literal('*')]);
+        assert_eq!(parse_spans(r"\*", false), vec![Literal('*')]);
+        assert_eq!(
+            parse_spans(r#"\"a\'"#, false),
+            vec![Literal('"'), Text("a".to_owned()), Literal('\'')]
+        );
+    }
+
+    #[test]
+    fn finds_math() {
+        assert_eq!(
+            parse_spans("where $a_i * b_i$ is *small*", false),
+            vec![
+                Text("where ".to_owned()),
+                InlineMath("a_i * b_i".to_owned()),
+                Text(" is ".to_owned()),
+                Emphasis(vec![Text("small".to_owned())])
+            ]
+        );
+        assert_eq!(
+            parse_spans(r"costs \$5", false),
+            vec![
+                Text("costs ".to_owned()),
+                Literal('$'),
+                Text("5".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_intraword_underscores() {
+        assert_eq!(
+            parse_spans("snake_case_name and __init__", false),
+            vec![
+                Text("snake_case_name and ".to_owned()),
+                Strong(vec![Text("init".to_owned())])
+            ]
+        );
+        assert_eq!(
+            parse_spans("_a_b_ c", false),
+            vec![
+                Emphasis(vec![Text("a_b".to_owned())]),
+                Text(" c".to_owned())
+            ]
+        );
     }
 
     #[test]
     fn finds_breaks() {
         assert_eq!(
-            parse_spans("this is a test  "),
+            parse_spans("this is a test  ", false),
             vec![Text("this is a test".to_owned()), Break]
         );
+        assert_eq!(
+            parse_spans("this is a test\\", false),
+            vec![Text("this is a test".to_owned()), Break]
+        );
+        assert_eq!(
+            parse_spans("this is a test\\\\", false),
+            vec![Text("this is a test".to_owned()), Literal('\\')]
+        );
     }
 
     #[test]
     fn finds_code() {
         assert_eq!(
-            parse_spans("this `is a` test"),
+            parse_spans("this `is a` test", false),
             vec![
                 Text("this ".to_owned()),
                 Code("is a".to_owned()),
@@ -124,7 +230,7 @@ mod test {
             ]
         );
         assert_eq!(
-            parse_spans("this ``is a`` test"),
+            parse_spans("this ``is a`` test", false),
             vec![
                 Text("this ".to_owned()),
                 Code("is a".to_owned()),
@@ -136,7 +242,7 @@ mod test {
     #[test]
     fn finds_emphasis() {
         assert_eq!(
-            parse_spans("this _is a_ test"),
+            parse_spans("this _is a_ test", false),
             vec![
                 Text("this ".to_owned()),
                 Emphasis(vec![Text("is a".to_owned())]),
@@ -144,7 +250,7 @@ mod test {
             ]
         );
         assert_eq!(
-            parse_spans("this *is a* test"),
+            parse_spans("this *is a* test", false),
             vec![
                 Text("this ".to_owned()),
                 Emphasis(vec![Text("is a".to_owned())]),
@@ -156,7 +262,7 @@ mod test {
     #[test]
     fn finds_strong() {
         assert_eq!(
-            parse_spans("this __is a__ test"),
+            parse_spans("this __is a__ test", false),
             vec![
                 Text("this ".to_owned()),
                 Strong(vec![Text("is a".to_owned())]),
@@ -164,7 +270,7 @@ mod test {
             ]
         );
         assert_eq!(
-            parse_spans("this **is a** test"),
+            parse_spans("this **is a** test", false),
             vec![
                 Text("this ".to_owned()),
                 Strong(vec![Text("is a".to_owned())]),
@@ -173,15 +279,82 @@ mod test {
         );
     }
 
+    #[test]
+    fn finds_strikethrough() {
+        assert_eq!(
+            parse_spans("this ~~is a~~ test", false),
+            vec![
+                Text("this ".to_owned()),
+                Strikethrough(vec![Text("is a".to_owned())]),
+                Text(" test".to_owned())
+            ]
+        );
+        assert_eq!(
+            parse_spans("this ~~is **a**~~ test", false),
+            vec![
+                Text("this ".to_owned()),
+                Strikethrough(vec![
+                    Text("is ".to_owned()),
+                    Strong(vec![Text("a".to_owned())])
+                ]),
+                Text(" test".to_owned())
+            ]
+        );
+        assert_eq!(
+            parse_spans(r"this \~~is not~~ a test", false),
+            vec![
+                Text("this ".to_owned()),
+                Literal('~'),
+                Text("~is not~~ a test".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_superscript_subscript_and_mark() {
+        assert_eq!(
+            parse_spans("H~2~O and 2^10^ are ==important==", false),
+            vec![
+                Text("H".to_owned()),
+                Subscript(vec![Text("2".to_owned())]),
+                Text("O and 2".to_owned()),
+                Superscript(vec![Text("10".to_owned())]),
+                Text(" are ".to_owned()),
+                Mark(vec![Text("important".to_owned())])
+            ]
+        );
+        assert_eq!(
+            parse_spans("~~gone~~ but ==*still* `x^2^`==", false),
+            vec![
+                Strikethrough(vec![Text("gone".to_owned())]),
+                Text(" but ".to_owned()),
+                Mark(vec![
+                    Emphasis(vec![Text("still".to_owned())]),
+                    Text(" ".to_owned()),
+                    Code("x^2^".to_owned())
+                ])
+            ]
+        );
+        assert_eq!(
+            parse_spans(r"a \^b^ and c == d", false),
+            vec![
+                Text("a ".to_owned()),
+                Literal('^'),
+                Text("b^ and c == d".to_owned())
+            ]
+        );
+    }
+
     #[test]
     fn finds_link() {
         assert_eq!(
-            parse_spans("this is [an example](example.com) test"),
+            parse_spans("this is [an example](example.com) test", false),
             vec![
                 Text("this is ".to_owned()),
                 Link(
                     vec![Text("an example".to_owned())],
                     "example.com".to_owned(),
+                    None,
                     None
                 ),
                 Text(" test".to_owned())
@@ -192,10 +365,15 @@ mod test {
     #[test]
     fn finds_image() {
         assert_eq!(
-            parse_spans("this is ![an example](example.com) test"),
+            parse_spans("this is ![an example](example.com) test", false),
             vec![
                 Text("this is ".to_owned()),
-                Image("an example".to_owned(), "example.com".to_owned(), None),
+                Image(
+                    "an example".to_owned(),
+                    "example.com".to_owned(),
+                    None,
+                    None
+                ),
                 Text(" test".to_owned())
             ]
         );
@@ -204,10 +382,10 @@ mod test {
     #[test]
     fn finds_everything() {
         assert_eq!(
-            parse_spans("some text ![an image](image.com) _emphasis_ __strong__ `teh codez` [a link](example.com)  "),
+            parse_spans("some text ![an image](image.com) _emphasis_ __strong__ `teh codez` [a link](example.com)  ", false),
             vec![
             Text("some text ".to_owned()),
-            Image("an image".to_owned(), "image.com".to_owned(), None),
+            Image("an image".to_owned(), "image.com".to_owned(), None, None),
             Text(" ".to_owned()),
             Emphasis(vec![Text("emphasis".to_owned())]),
             Text(" ".to_owned()),
@@ -215,7 +393,7 @@ mod test {
             Text(" ".to_owned()),
             Code("teh codez".to_owned()),
             Text(" ".to_owned()),
-            Link(vec![Text("a link".to_owned())], "example.com".to_owned(), None),
+            Link(vec![Text("a link".to_owned())], "example.com".to_owned(), None, None),
             Break
             ]
             );
@@ -224,6 +402,6 @@ mod test {
     #[test]
     fn properly_consumes_multibyte_utf8() {
         let test_phrase = str::from_utf8(b"This shouldn\xE2\x80\x99t panic").unwrap();
-        let _ = parse_spans(&test_phrase);
+        let _ = parse_spans(test_phrase, false);
     }
 }
@@ -0,0 +1,213 @@
+use options::References;
+use parser::ReferenceKind;
+use parser::Span;
+use parser::Span::{Reference, Text};
+use regex::{Captures, Regex};
+
+// Finds issue references like `#123` and `owner/repo#123`, commit SHAs
+// and `@handle` mentions in a text and splits it into text and reference
+// spans, linking them with the url templates of `references`.
+pub fn find_references(text: &str, references: &References) -> Vec<Span> {
+    lazy_static! {
+        static ref REFERENCE: Regex = Regex::new(
+            r"(?:(?P<repository>[A-Za-z0-9][A-Za-z0-9-]*/[A-Za-z0-9._-]+)(?:#(?P<cross_number>[0-9]+)|@(?P<cross_sha>[0-9a-f]{7,40}))|#(?P<number>[0-9]+)|(?P<sha>[0-9a-f]{7,40})|@(?P<user>[A-Za-z0-9](?:-?[A-Za-z0-9])*))\b"
+        )
+        .unwrap();
+    }
+
+    let mut spans = vec![];
+    let mut start = 0;
+    for caps in REFERENCE.captures_iter(text) {
+        let mat = caps.get(0).unwrap();
+
+        // references have to be at the beginning of a line, after whitespace
+        // or after an opening bracket, a quote or a delimiter
+        match text[..mat.start()].chars().last() {
+            None => {}
+            Some(c) if c.is_whitespace() || "([{\"'*_~".contains(c) => {}
+            _ => continue,
+        }
+
+        let (kind, url) = match reference_url(&caps, references) {
+            Some(reference) => reference,
+            None => continue,
+        };
+
+        if start < mat.start() {
+            spans.push(Text(text[start..mat.start()].to_owned()));
+        }
+        spans.push(Reference(kind, mat.as_str().to_owned(), url));
+        start = mat.end();
+    }
+    if start < text.len() {
+        spans.push(Text(text[start..].to_owned()));
+    }
+    spans
+}
+
+fn reference_url(caps: &Captures, references: &References) -> Option<(ReferenceKind, String)> {
+    let repository = caps
+        .name("repository")
+        .map_or(references.repository.as_str(), |m| m.as_str());
+
+    if let Some(number) = caps.name("number").or_else(|| caps.name("cross_number")) {
+        let url = references
+            .issue_url
+            .replace("{repository}", repository)
+            .replace("{number}", number.as_str());
+        return Some((ReferenceKind::Issue, url));
+    }
+
+    if let Some(sha) = caps.name("sha").or_else(|| caps.name("cross_sha")) {
+        // hex words like `defaced` and numbers like `1234567` are no SHAs
+        let sha = sha.as_str();
+        if !sha.contains(|c: char| c.is_ascii_digit())
+            || !sha.contains(|c: char| c.is_ascii_alphabetic())
+        {
+            return None;
+        }
+        let url = references
+            .commit_url
+            .replace("{repository}", repository)
+            .replace("{sha}", sha);
+        return Some((ReferenceKind::Commit, url));
+    }
+
+    let user = caps.name("user").unwrap().as_str();
+    if let Some(ref filter) = references.mention_filter {
+        if !filter.accept(user) {
+            return None;
+        }
+    }
+    Some((
+        ReferenceKind::Mention,
+        references.mention_url.replace("{user}", user),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::find_references;
+    use options::References;
+    use parser::ReferenceKind::{Commit, Issue, Mention};
+    use parser::Span::{Reference, Text};
+    use std::sync::Arc;
+
+    #[test]
+    fn finds_references() {
+        assert_eq!(
+            find_references(
+                "Fixes #12 and rust-lang/rust#345 (thanks @octo-cat)",
+                &References::github("acme/widgets")
+            ),
+            vec![
+                Text("Fixes ".to_owned()),
+                Reference(
+                    Issue,
+                    "#12".to_owned(),
+                    "https://github.com/acme/widgets/issues/12".to_owned()
+                ),
+                Text(" and ".to_owned()),
+                Reference(
+                    Issue,
+                    "rust-lang/rust#345".to_owned(),
+                    "https://github.com/rust-lang/rust/issues/345".to_owned()
+                ),
+                Text(" (thanks ".to_owned()),
+                Reference(
+                    Mention,
+                    "@octo-cat".to_owned(),
+                    "https://github.com/octo-cat".to_owned()
+                ),
+                Text(")".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_commits() {
+        assert_eq!(
+            find_references(
+                "in a5c3785, acme/tools@0f1e2d3c4b.",
+                &References::github("acme/widgets")
+            ),
+            vec![
+                Text("in ".to_owned()),
+                Reference(
+                    Commit,
+                    "a5c3785".to_owned(),
+                    "https://github.com/acme/widgets/commit/a5c3785".to_owned()
+                ),
+                Text(", ".to_owned()),
+                Reference(
+                    Commit,
+                    "acme/tools@0f1e2d3c4b".to_owned(),
+                    "https://github.com/acme/tools/commit/0f1e2d3c4b".to_owned()
+                ),
+                Text(".".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_templates() {
+        let references = References {
+            issue_url: "https://tracker.example.com/{repository}/{number}".to_owned(),
+            mention_url: "/people/{user}".to_owned(),
+            ..References::github("widgets")
+        };
+        assert_eq!(
+            find_references("#7 by @ana", &references),
+            vec![
+                Reference(
+                    Issue,
+                    "#7".to_owned(),
+                    "https://tracker.example.com/widgets/7".to_owned()
+                ),
+                Text(" by ".to_owned()),
+                Reference(Mention, "@ana".to_owned(), "/people/ana".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_mentions() {
+        let references = References {
+            mention_filter: Some(Arc::new(|handle: &str| handle != "everyone")),
+            ..References::github("acme/widgets")
+        };
+        assert_eq!(
+            find_references("@everyone @ana", &references),
+            vec![
+                Text("@everyone ".to_owned()),
+                Reference(
+                    Mention,
+                    "@ana".to_owned(),
+                    "https://github.com/ana".to_owned()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        let references = References::github("acme/widgets");
+        for text in &[
+            "issue#12",
+            "#12abc",
+            "&#123;",
+            "mail@example.com",
+            "@user_name",
+            "a/b/c#1",
+            "deadbeef",
+            "1234567",
+            "abc123",
+            "xa5c3785",
+        ] {
+            assert_eq!(
+                find_references(text, &references),
+                vec![Text((*text).to_owned())]
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use parser::span::parse_spans;
+use parser::Span;
+use parser::Span::Strikethrough;
+use regex::Regex;
+
+pub fn parse_strikethrough(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref STRIKETHROUGH: Regex =
+            Regex::new(r"^~~(?P<text>[^~\s](?:.*?[^~\s])?)~~").unwrap();
+    }
+
+    if STRIKETHROUGH.is_match(text) {
+        let caps = STRIKETHROUGH.captures(text).unwrap();
+        let t = caps.name("text").unwrap().as_str();
+        return Some((
+            Strikethrough(parse_spans(t, extended_autolinks)),
+            t.len() + 4,
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_strikethrough;
+    use parser::Span::{Emphasis, Strikethrough, Text};
+
+    #[test]
+    fn finds_strikethrough() {
+        assert_eq!(
+            parse_strikethrough("~~testing things~~ test", false),
+            Some((Strikethrough(vec![Text("testing things".to_owned())]), 18))
+        );
+
+        assert_eq!(
+            parse_strikethrough("~~testing things~~ things~~ test", false),
+            Some((Strikethrough(vec![Text("testing things".to_owned())]), 18))
+        );
+
+        assert_eq!(
+            parse_strikethrough("~~w~~ test", false),
+            Some((Strikethrough(vec![Text("w".to_owned())]), 5))
+        );
+
+        assert_eq!(
+            parse_strikethrough("~~*w*~~ test", false),
+            Some((Strikethrough(vec![Emphasis(vec![Text("w".to_owned())])]), 7))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_strikethrough("~~ testing things~~ test", false), None);
+        assert_eq!(parse_strikethrough("~~testing things ~~ test", false), None);
+        assert_eq!(parse_strikethrough("~~~~ test", false), None);
+        assert_eq!(parse_strikethrough("~testing things~ test", false), None);
+        assert_eq!(parse_strikethrough("~~ test", false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(
+            parse_strikethrough("were ~~testing things~~ test", false),
+            None
+        );
+    }
+}
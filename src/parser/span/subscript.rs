@@ -0,0 +1,50 @@
+use parser::span::parse_spans;
+use parser::Span;
+use parser::Span::Subscript;
+use regex::Regex;
+
+pub fn parse_subscript(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
+    lazy_static! {
+        // like in Pandoc, spaces have to be escaped and `~~` is left to strikethrough
+        static ref SUBSCRIPT: Regex = Regex::new(r"^~(?P<text>(?:[^\s~\\]|\\.)+)~").unwrap();
+    }
+
+    if SUBSCRIPT.is_match(text) {
+        let caps = SUBSCRIPT.captures(text).unwrap();
+        let t = caps.name("text").unwrap().as_str();
+        return Some((Subscript(parse_spans(t, extended_autolinks)), t.len() + 2));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_subscript;
+    use parser::Span::{Subscript, Text};
+
+    #[test]
+    fn finds_subscript() {
+        assert_eq!(
+            parse_subscript("~2~O test", false),
+            Some((Subscript(vec![Text("2".to_owned())]), 3))
+        );
+
+        assert_eq!(
+            parse_subscript("~max~ test~", false),
+            Some((Subscript(vec![Text("max".to_owned())]), 5))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_subscript("~~ test", false), None);
+        assert_eq!(parse_subscript("~~strike~~ test", false), None);
+        assert_eq!(parse_subscript("~two words~ test", false), None);
+        assert_eq!(parse_subscript("~2 test", false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_subscript("H~2~O", false), None);
+    }
+}
@@ -0,0 +1,62 @@
+use parser::span::parse_spans;
+use parser::Span;
+use parser::Span::Superscript;
+use regex::Regex;
+
+pub fn parse_superscript(text: &str, extended_autolinks: bool) -> Option<(Span, usize)> {
+    lazy_static! {
+        // like in Pandoc, spaces have to be escaped
+        static ref SUPERSCRIPT: Regex = Regex::new(r"^\^(?P<text>(?:[^\s^\\]|\\.)+)\^").unwrap();
+    }
+
+    if SUPERSCRIPT.is_match(text) {
+        let caps = SUPERSCRIPT.captures(text).unwrap();
+        let t = caps.name("text").unwrap().as_str();
+        return Some((Superscript(parse_spans(t, extended_autolinks)), t.len() + 2));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_superscript;
+    use parser::Span::{Emphasis, Literal, Superscript, Text};
+
+    #[test]
+    fn finds_superscript() {
+        assert_eq!(
+            parse_superscript("^2^ test", false),
+            Some((Superscript(vec![Text("2".to_owned())]), 3))
+        );
+
+        assert_eq!(
+            parse_superscript("^*th*^ test", false),
+            Some((Superscript(vec![Emphasis(vec![Text("th".to_owned())])]), 6))
+        );
+
+        assert_eq!(
+            parse_superscript(r"^a\^b^ test", false),
+            Some((
+                Superscript(vec![
+                    Text("a".to_owned()),
+                    Literal('^'),
+                    Text("b".to_owned())
+                ]),
+                6
+            ))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_superscript("^^ test", false), None);
+        assert_eq!(parse_superscript("^two words^ test", false), None);
+        assert_eq!(parse_superscript("^ 2^ test", false), None);
+        assert_eq!(parse_superscript("^2 test", false), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_superscript("x^2^ test", false), None);
+    }
+}
@@ -0,0 +1,62 @@
+use parser::Span;
+use parser::Span::WikiLink;
+use regex::Regex;
+
+pub fn parse_wiki_link(text: &str) -> Option<(Span, usize)> {
+    lazy_static! {
+        static ref WIKI_LINK: Regex =
+            Regex::new(r"^\[\[(?P<page>[^\[\]|]+)(?:\|(?P<label>[^\[\]]+))?\]\]").unwrap();
+    }
+
+    let caps = WIKI_LINK.captures(text)?;
+    let page = caps.name("page").unwrap().as_str().trim();
+    if page.is_empty() {
+        return None;
+    }
+    let label = caps
+        .name("label")
+        .map(|label| label.as_str().trim().to_owned());
+    Some((WikiLink(page.to_owned(), label), caps[0].len()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_wiki_link;
+    use parser::Span::WikiLink;
+
+    #[test]
+    fn finds_wiki_link() {
+        assert_eq!(
+            parse_wiki_link("[[Page Name]] test"),
+            Some((WikiLink("Page Name".to_owned(), None), 13))
+        );
+
+        assert_eq!(
+            parse_wiki_link("[[Page Name|the label]] test"),
+            Some((
+                WikiLink("Page Name".to_owned(), Some("the label".to_owned())),
+                23
+            ))
+        );
+
+        assert_eq!(
+            parse_wiki_link("[[ Page | label ]]"),
+            Some((WikiLink("Page".to_owned(), Some("label".to_owned())), 18))
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        assert_eq!(parse_wiki_link("[[]] test"), None);
+        assert_eq!(parse_wiki_link("[[ ]] test"), None);
+        assert_eq!(parse_wiki_link("[[|label]] test"), None);
+        assert_eq!(parse_wiki_link("[[Page] test"), None);
+        assert_eq!(parse_wiki_link("[Page]] test"), None);
+        assert_eq!(parse_wiki_link("[[[Page]]] test"), None);
+    }
+
+    #[test]
+    fn no_early_matching() {
+        assert_eq!(parse_wiki_link("test [[Page]]"), None);
+    }
+}
@@ -0,0 +1,238 @@
+use parser::Block::{
+    Admonition, Blockquote, ContainerDirective, DefinitionList, FootnoteDefinition, Header,
+    LeafDirective, OrderedList, Paragraph, Table, UnorderedList,
+};
+use parser::Span::{
+    Emphasis, InlineDirective, Link, Mark, RefLink, Strikethrough, Strong, Subscript, Superscript,
+    Text,
+};
+use parser::{Block, ListItem, Span};
+
+// Replaces every text span outside of code with the spans returned by `f`.
+// The contents of links are only visited if `in_links` is set.
+pub fn map_text<F>(blocks: Vec<Block>, in_links: bool, f: &F) -> Vec<Block>
+where
+    F: Fn(&str) -> Vec<Span>,
+{
+    map_inlines(blocks, &|spans| map_spans(spans, in_links, f))
+}
+
+// Replaces the content of every block made of spans, like paragraphs,
+// headers or table cells, with the spans returned by `f`.
+pub fn map_inlines<F>(blocks: Vec<Block>, f: &F) -> Vec<Block>
+where
+    F: Fn(Vec<Span>) -> Vec<Span>,
+{
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            Header(spans, level, attributes) => Header(f(spans), level, attributes),
+            Paragraph(spans) => Paragraph(f(spans)),
+            Blockquote(blocks) => Blockquote(map_inlines(blocks, f)),
+            Admonition(kind, blocks) => Admonition(kind, map_inlines(blocks, f)),
+            LeafDirective(name, label, attributes) => LeafDirective(name, f(label), attributes),
+            ContainerDirective(name, label, attributes, blocks) => {
+                ContainerDirective(name, f(label), attributes, map_inlines(blocks, f))
+            }
+            OrderedList(items, num_type, start, delimiter) => {
+                OrderedList(map_items(items, f), num_type, start, delimiter)
+            }
+            UnorderedList(items) => UnorderedList(map_items(items, f)),
+            DefinitionList(entries) => DefinitionList(
+                entries
+                    .into_iter()
+                    .map(|(term, definitions)| {
+                        (
+                            f(term),
+                            definitions
+                                .into_iter()
+                                .map(|blocks| map_inlines(blocks, f))
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            ),
+            Table(header, alignments, rows) => Table(
+                header.into_iter().map(f).collect(),
+                alignments,
+                rows.into_iter()
+                    .map(|row| row.into_iter().map(f).collect())
+                    .collect(),
+            ),
+            FootnoteDefinition(id, blocks) => FootnoteDefinition(id, map_inlines(blocks, f)),
+            block => block,
+        })
+        .collect()
+}
+
+// Replaces every block that doesn't contain other blocks, like paragraphs,
+// with the blocks returned by `f`. The blocks returned by `f` aren't visited.
+pub fn flat_map_blocks<F>(blocks: Vec<Block>, f: &F) -> Vec<Block>
+where
+    F: Fn(Block) -> Vec<Block>,
+{
+    let map_items = |items: Vec<ListItem>| {
+        items
+            .into_iter()
+            .map(|item| match item {
+                ListItem::Paragraph(blocks, checked) => {
+                    ListItem::Paragraph(flat_map_blocks(blocks, f), checked)
+                }
+                item => item,
+            })
+            .collect()
+    };
+
+    let mut ret = vec![];
+    for block in blocks {
+        match block {
+            Blockquote(blocks) => ret.push(Blockquote(flat_map_blocks(blocks, f))),
+            Admonition(kind, blocks) => ret.push(Admonition(kind, flat_map_blocks(blocks, f))),
+            ContainerDirective(name, label, attributes, blocks) => ret.push(ContainerDirective(
+                name,
+                label,
+                attributes,
+                flat_map_blocks(blocks, f),
+            )),
+            OrderedList(items, num_type, start, delimiter) => {
+                ret.push(OrderedList(map_items(items), num_type, start, delimiter))
+            }
+            UnorderedList(items) => ret.push(UnorderedList(map_items(items))),
+            DefinitionList(entries) => ret.push(DefinitionList(
+                entries
+                    .into_iter()
+                    .map(|(term, definitions)| {
+                        (
+                            term,
+                            definitions
+                                .into_iter()
+                                .map(|blocks| flat_map_blocks(blocks, f))
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            )),
+            FootnoteDefinition(id, blocks) => {
+                ret.push(FootnoteDefinition(id, flat_map_blocks(blocks, f)))
+            }
+            block => ret.extend(f(block)),
+        }
+    }
+    ret
+}
+
+fn map_items<F>(items: Vec<ListItem>, f: &F) -> Vec<ListItem>
+where
+    F: Fn(Vec<Span>) -> Vec<Span>,
+{
+    items
+        .into_iter()
+        .map(|item| match item {
+            ListItem::Simple(spans, checked) => ListItem::Simple(f(spans), checked),
+            ListItem::Paragraph(blocks, checked) => {
+                ListItem::Paragraph(map_inlines(blocks, f), checked)
+            }
+        })
+        .collect()
+}
+
+fn map_spans<F>(spans: Vec<Span>, in_links: bool, f: &F) -> Vec<Span>
+where
+    F: Fn(&str) -> Vec<Span>,
+{
+    let mut ret = vec![];
+    for span in spans {
+        match span {
+            Text(text) => ret.extend(f(&text)),
+            Emphasis(content) => ret.push(Emphasis(map_spans(content, in_links, f))),
+            Strong(content) => ret.push(Strong(map_spans(content, in_links, f))),
+            Strikethrough(content) => ret.push(Strikethrough(map_spans(content, in_links, f))),
+            Superscript(content) => ret.push(Superscript(map_spans(content, in_links, f))),
+            Subscript(content) => ret.push(Subscript(map_spans(content, in_links, f))),
+            Mark(content) => ret.push(Mark(map_spans(content, in_links, f))),
+            InlineDirective(name, label, attributes) => ret.push(InlineDirective(
+                name,
+                map_spans(label, in_links, f),
+                attributes,
+            )),
+            Link(content, url, title, attributes) if in_links => ret.push(Link(
+                map_spans(content, in_links, f),
+                url,
+                title,
+                attributes,
+            )),
+            RefLink(content, reference, raw) if in_links => {
+                ret.push(RefLink(map_spans(content, in_links, f), reference, raw))
+            }
+            span => ret.push(span),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::map_text;
+    use parser::Block::{Blockquote, CodeBlock, Paragraph};
+    use parser::Span;
+    use parser::Span::{Code, Link, Strong, Text};
+
+    fn shout(text: &str) -> Vec<Span> {
+        vec![Text(text.to_uppercase())]
+    }
+
+    #[test]
+    fn maps_text() {
+        assert_eq!(
+            map_text(
+                vec![
+                    Blockquote(vec![Paragraph(vec![
+                        Text("a ".to_owned()),
+                        Strong(vec![Text("b".to_owned())]),
+                        Code("c".to_owned())
+                    ])]),
+                    CodeBlock(None, "d".to_owned(), None)
+                ],
+                false,
+                &shout
+            ),
+            vec![
+                Blockquote(vec![Paragraph(vec![
+                    Text("A ".to_owned()),
+                    Strong(vec![Text("B".to_owned())]),
+                    Code("c".to_owned())
+                ])]),
+                CodeBlock(None, "d".to_owned(), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_links() {
+        let link = Paragraph(vec![Link(
+            vec![Text("a".to_owned())],
+            "b".to_owned(),
+            None,
+            None,
+        )]);
+        assert_eq!(map_text(vec![link.clone()], false, &shout), vec![link]);
+        assert_eq!(
+            map_text(
+                vec![Paragraph(vec![Link(
+                    vec![Text("a".to_owned())],
+                    "b".to_owned(),
+                    None,
+                    None
+                )])],
+                true,
+                &shout
+            ),
+            vec![Paragraph(vec![Link(
+                vec![Text("A".to_owned())],
+                "b".to_owned(),
+                None,
+                None
+            )])]
+        );
+    }
+}
@@ -0,0 +1,4 @@
+// An example
+fn main() {
+    println!("Hello <world>!");
+}
@@ -3,6 +3,7 @@ use markdown;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
 
 fn compare(name: &str) {
     let html = format!("tests/fixtures/files/{}.html", name);
@@ -43,6 +44,63 @@ fn roundtrip(name: &str) {
     difference::assert_diff(&comp, &markdown::to_html(&out), " ", 0);
 }
 
+fn compare_with_options(name: &str, options: &markdown::Options) {
+    let html = format!("tests/fixtures/files/{}.html", name);
+    let text = format!("tests/fixtures/files/{}.text", name);
+    let mut comp = String::new();
+    File::open(Path::new(&html))
+        .unwrap()
+        .read_to_string(&mut comp)
+        .unwrap();
+
+    let mut tokens = String::new();
+    File::open(Path::new(&text))
+        .unwrap()
+        .read_to_string(&mut tokens)
+        .unwrap();
+    println!(
+        "{:?} -> {:?}",
+        tokens,
+        markdown::tokenize_with_options(&tokens, options)
+    );
+
+    difference::assert_diff(
+        &comp,
+        &markdown::file_to_html_with_options(Path::new(&text), options).unwrap(),
+        " ",
+        0,
+    );
+}
+
+fn roundtrip_with_options(name: &str, options: &markdown::Options) {
+    let html = format!("tests/fixtures/files/{}.html", name);
+    let text = format!("tests/fixtures/files/{}.text", name);
+    let mut comp = String::new();
+    File::open(Path::new(&html))
+        .unwrap()
+        .read_to_string(&mut comp)
+        .unwrap();
+
+    let mut tokens = String::new();
+    File::open(Path::new(&text))
+        .unwrap()
+        .read_to_string(&mut tokens)
+        .unwrap();
+
+    let v = markdown::tokenize_with_options(&tokens, options);
+    println!("{:?}", v);
+    let out = markdown::generate_markdown(v);
+
+    println!("BEGIN\n{}\nEND", out);
+
+    difference::assert_diff(
+        &comp,
+        &markdown::to_html_with_options(&out, options),
+        " ",
+        0,
+    );
+}
+
 #[test]
 pub fn alt() {
     compare("alt")
@@ -218,10 +276,10 @@ pub fn lists_ol() {
     compare("lists_ol")
 }
 
-//#[test]
-//pub fn rt_lists_ol() {
-//roundtrip("lists_ol")
-//}
+#[test]
+pub fn rt_lists_ol() {
+    roundtrip("lists_ol")
+}
 
 #[test]
 pub fn links() {
@@ -258,10 +316,10 @@ pub fn olist() {
     compare("olist")
 }
 
-//#[test]
-//pub fn rt_olist() {
-//roundtrip("olist")
-//}
+#[test]
+pub fn rt_olist() {
+    roundtrip("olist")
+}
 
 #[test]
 pub fn paragraph() {
@@ -312,3 +370,504 @@ pub fn wrapping() {
 pub fn rt_wrapping() {
     roundtrip("wrapping")
 }
+
+#[test]
+pub fn tables() {
+    compare("tables")
+}
+
+#[test]
+pub fn rt_tables() {
+    roundtrip("tables")
+}
+
+#[test]
+pub fn strikethrough() {
+    compare("strikethrough")
+}
+
+#[test]
+pub fn rt_strikethrough() {
+    roundtrip("strikethrough")
+}
+
+#[test]
+pub fn task_lists() {
+    compare("task_lists")
+}
+
+#[test]
+pub fn footnotes() {
+    compare("footnotes")
+}
+
+#[test]
+pub fn rt_footnotes() {
+    roundtrip("footnotes")
+}
+
+#[test]
+pub fn autolinks() {
+    compare("autolinks")
+}
+
+#[test]
+pub fn rt_autolinks() {
+    roundtrip("autolinks")
+}
+
+#[test]
+pub fn extended_autolinks() {
+    compare_with_options(
+        "extended_autolinks",
+        &markdown::Options {
+            extended_autolinks: true,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+pub fn rt_extended_autolinks() {
+    roundtrip_with_options(
+        "extended_autolinks",
+        &markdown::Options {
+            extended_autolinks: true,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+pub fn html() {
+    compare("html")
+}
+
+#[test]
+pub fn rt_html() {
+    roundtrip("html")
+}
+
+#[test]
+pub fn obfuscated_emails() {
+    compare_with_options(
+        "obfuscated_emails",
+        &markdown::Options {
+            obfuscate_emails: true,
+            email_obfuscation_seed: 42,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+pub fn rt_obfuscated_emails() {
+    roundtrip_with_options(
+        "obfuscated_emails",
+        &markdown::Options {
+            obfuscate_emails: true,
+            email_obfuscation_seed: 42,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+pub fn definition_lists() {
+    compare("definition_lists")
+}
+
+#[test]
+pub fn rt_definition_lists() {
+    roundtrip("definition_lists")
+}
+
+#[test]
+pub fn math() {
+    compare("math")
+}
+
+#[test]
+pub fn rt_math() {
+    roundtrip("math")
+}
+
+#[test]
+pub fn front_matter() {
+    compare("front_matter")
+}
+
+#[test]
+pub fn rt_front_matter() {
+    roundtrip("front_matter")
+}
+
+#[test]
+pub fn front_matter_toml() {
+    compare("front_matter_toml")
+}
+
+#[test]
+pub fn rt_front_matter_toml() {
+    roundtrip("front_matter_toml")
+}
+
+#[test]
+pub fn front_matter_fields() {
+    let mut text = String::new();
+    File::open(Path::new("tests/fixtures/files/front_matter.text"))
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+
+    let front_matter = markdown::front_matter(&text).unwrap();
+    assert_eq!(front_matter.format, markdown::FrontMatterFormat::Yaml);
+    assert_eq!(front_matter.raw, "title: \"Front matter\"\ntags:\n  - docs");
+    assert_eq!(front_matter.get("title"), Some("Front matter"));
+    assert_eq!(front_matter.get("tags"), None);
+}
+
+#[test]
+pub fn attributes() {
+    compare("attributes")
+}
+
+#[test]
+pub fn rt_attributes() {
+    roundtrip("attributes")
+}
+
+#[test]
+pub fn admonitions() {
+    compare("admonitions")
+}
+
+#[test]
+pub fn rt_admonitions() {
+    roundtrip("admonitions")
+}
+
+fn emoji_options() -> markdown::Options {
+    let mut options = markdown::Options {
+        emoji_shortcodes: true,
+        ..Default::default()
+    };
+    options
+        .custom_emoji
+        .insert("shipit".to_owned(), "/emoji/shipit.png".to_owned());
+    options
+}
+
+#[test]
+pub fn emoji() {
+    compare_with_options("emoji", &emoji_options())
+}
+
+#[test]
+pub fn rt_emoji() {
+    roundtrip_with_options("emoji", &emoji_options())
+}
+
+#[test]
+pub fn wiki_links() {
+    compare("wiki_links")
+}
+
+#[test]
+pub fn rt_wiki_links() {
+    roundtrip("wiki_links")
+}
+
+struct Wiki;
+
+impl markdown::WikiLinkResolver for Wiki {
+    fn url(&self, page: &str) -> String {
+        format!("/wiki/{}.html", page.to_lowercase().replace(' ', "-"))
+    }
+
+    fn exists(&self, page: &str) -> bool {
+        page != "Missing Page"
+    }
+}
+
+#[test]
+pub fn wiki_links_resolved() {
+    compare_with_options(
+        "wiki_links_resolved",
+        &markdown::Options {
+            wiki_link_resolver: Some(Arc::new(Wiki)),
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+pub fn rt_wiki_links_resolved() {
+    roundtrip_with_options(
+        "wiki_links_resolved",
+        &markdown::Options {
+            wiki_link_resolver: Some(Arc::new(Wiki)),
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+pub fn abbreviations() {
+    compare("abbreviations")
+}
+
+#[test]
+pub fn rt_abbreviations() {
+    roundtrip("abbreviations")
+}
+
+#[test]
+pub fn sup_sub_mark() {
+    compare("sup_sub_mark")
+}
+
+#[test]
+pub fn rt_sup_sub_mark() {
+    roundtrip("sup_sub_mark")
+}
+
+fn smart_options(quote_style: markdown::QuoteStyle) -> markdown::Options {
+    markdown::Options {
+        smart_punctuation: true,
+        quote_style,
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn smart_punctuation() {
+    compare_with_options(
+        "smart_punctuation",
+        &smart_options(markdown::QuoteStyle::english()),
+    )
+}
+
+#[test]
+pub fn rt_smart_punctuation() {
+    roundtrip_with_options(
+        "smart_punctuation",
+        &smart_options(markdown::QuoteStyle::english()),
+    )
+}
+
+#[test]
+pub fn smart_punctuation_german() {
+    compare_with_options(
+        "smart_punctuation_german",
+        &smart_options(markdown::QuoteStyle::german()),
+    )
+}
+
+#[test]
+pub fn smart_punctuation_french() {
+    compare_with_options(
+        "smart_punctuation_french",
+        &smart_options(markdown::QuoteStyle::french()),
+    )
+}
+
+#[test]
+pub fn plain_punctuation() {
+    let text = r#""Don't" -- change..."#;
+    assert_eq!(
+        markdown::to_html(text),
+        "<p>&quot;Don't&quot; -- change...</p>\n"
+    );
+}
+
+#[test]
+pub fn code_fences() {
+    compare("code_fences")
+}
+
+#[test]
+pub fn rt_code_fences() {
+    roundtrip("code_fences")
+}
+
+#[test]
+pub fn ordered_lists() {
+    compare("ordered_lists")
+}
+
+#[test]
+pub fn rt_ordered_lists() {
+    roundtrip("ordered_lists")
+}
+
+#[test]
+pub fn ordered_lists_long() {
+    compare("ordered_lists_long")
+}
+
+#[test]
+pub fn rt_ordered_lists_long() {
+    roundtrip("ordered_lists_long")
+}
+
+#[test]
+pub fn line_breaks() {
+    compare("line_breaks")
+}
+
+#[test]
+pub fn rt_line_breaks() {
+    roundtrip("line_breaks")
+}
+
+fn hard_break_options() -> markdown::Options {
+    markdown::Options {
+        hard_breaks: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn line_breaks_hard() {
+    compare_with_options("line_breaks_hard", &hard_break_options())
+}
+
+#[test]
+pub fn rt_line_breaks_hard() {
+    roundtrip_with_options("line_breaks_hard", &hard_break_options())
+}
+
+#[test]
+pub fn directives() {
+    compare("directives")
+}
+
+#[test]
+pub fn rt_directives() {
+    roundtrip("directives")
+}
+
+fn render_youtube(directive: &markdown::Directive) -> String {
+    format!(
+        "<iframe src=\"https://www.youtube.com/embed/{}\" title=\"{}\"></iframe>",
+        directive.attributes.get("id").unwrap_or_default(),
+        directive.label
+    )
+}
+
+fn directive_options() -> markdown::Options {
+    let mut options = markdown::Options::default();
+    options
+        .directives
+        .insert("youtube".to_owned(), Arc::new(render_youtube));
+    options.directives.insert(
+        "note".to_owned(),
+        Arc::new(|directive: &markdown::Directive| {
+            assert_eq!(directive.kind, markdown::DirectiveKind::Container);
+            format!(
+                "<aside class=\"note\">\n<strong>{}</strong>\n{}</aside>",
+                directive.attributes.get("title").unwrap_or("Note"),
+                directive.content
+            )
+        }),
+    );
+    options
+}
+
+#[test]
+pub fn directives_handled() {
+    compare_with_options("directives_handled", &directive_options())
+}
+
+#[test]
+pub fn rt_directives_handled() {
+    roundtrip_with_options("directives_handled", &directive_options())
+}
+
+#[test]
+pub fn toc() {
+    compare("toc")
+}
+
+#[test]
+pub fn rt_toc() {
+    roundtrip("toc")
+}
+
+#[test]
+pub fn toc_escaping() {
+    compare("toc_escaping")
+}
+
+#[test]
+pub fn rt_toc_escaping() {
+    roundtrip("toc_escaping")
+}
+
+#[test]
+pub fn toc_link_reference() {
+    compare("toc_link_reference")
+}
+
+#[test]
+pub fn rt_toc_link_reference() {
+    roundtrip("toc_link_reference")
+}
+
+fn toc_options() -> markdown::Options {
+    markdown::Options {
+        toc_marker: Some("{{toc}}".to_owned()),
+        toc_levels: Some(2..=3),
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn toc_levels() {
+    compare_with_options("toc_levels", &toc_options())
+}
+
+#[test]
+pub fn rt_toc_levels() {
+    roundtrip_with_options("toc_levels", &toc_options())
+}
+
+fn reference_options() -> markdown::Options {
+    markdown::Options {
+        references: Some(markdown::References {
+            mention_filter: Some(Arc::new(|handle: &str| handle != "nobody")),
+            ..markdown::References::github("acme/widgets")
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn references() {
+    compare_with_options("references", &reference_options())
+}
+
+#[test]
+pub fn rt_references() {
+    roundtrip_with_options("references", &reference_options())
+}
+
+#[test]
+pub fn ref_images() {
+    compare("ref_images")
+}
+
+#[test]
+pub fn rt_ref_images() {
+    roundtrip("ref_images")
+}
+
+#[test]
+pub fn includes() {
+    let options = markdown::Options {
+        includes: true,
+        ..Default::default()
+    };
+    compare_with_options("includes", &options)
+}